@@ -0,0 +1,15 @@
+CREATE TABLE attempts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    room TEXT NOT NULL,
+    question INTEGER NOT NULL,
+    -- JSON array with the answer to each part.
+    answers TEXT NOT NULL,
+    correct BOOLEAN NOT NULL,
+    -- seconds of cooldown the attempt put the user on.
+    cooldown_secs INTEGER NOT NULL,
+    attempted_at INTEGER NOT NULL
+);
+
+CREATE INDEX idx_attempts_room_question ON attempts (room, question);
+CREATE INDEX idx_attempts_room_user ON attempts (room, user_id);
@@ -0,0 +1,22 @@
+CREATE TABLE splits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    room TEXT NOT NULL,
+    -- the question the user moved onto, past the last question means they won.
+    question INTEGER NOT NULL,
+    reached_at INTEGER NOT NULL
+);
+
+CREATE INDEX idx_splits_room_user ON splits (room, user_id);
+
+CREATE TABLE role_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    room TEXT NOT NULL,
+    added INTEGER,
+    removed INTEGER,
+    succeeded BOOLEAN NOT NULL,
+    changed_at INTEGER NOT NULL
+);
+
+CREATE INDEX idx_role_changes_room_user ON role_changes (room, user_id);
@@ -0,0 +1,16 @@
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor_id INTEGER NOT NULL,
+    -- the command that was run.
+    action TEXT NOT NULL,
+    -- the user the action was done to, if any.
+    target_id INTEGER,
+    arguments TEXT NOT NULL,
+    before TEXT,
+    after TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX idx_audit_log_actor ON audit_log (actor_id);
+CREATE INDEX idx_audit_log_target ON audit_log (target_id);
+CREATE INDEX idx_audit_log_action ON audit_log (action);
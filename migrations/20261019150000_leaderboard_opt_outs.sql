@@ -0,0 +1,5 @@
+-- players that are shown anonymously on public leaderboards.
+CREATE TABLE leaderboard_opt_outs (
+    user_id INTEGER PRIMARY KEY,
+    opted_out_at INTEGER NOT NULL
+);
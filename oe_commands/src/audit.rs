@@ -0,0 +1,141 @@
+use std::fmt::Write;
+
+use crate::{Context, Error};
+use oe_core::audit::AuditEntry;
+use poise::{
+    CreateReply,
+    serenity_prelude::{
+        Colour, CreateAllowedMentions, CreateAttachment, CreateEmbed, CreateMessage, User,
+    },
+};
+
+const DEFAULT_LIMIT: u32 = 20;
+/// The most Discord allows in an embed field value.
+const FIELD_LIMIT: usize = 1024;
+/// The most Discord allows in an embed description.
+const DESCRIPTION_LIMIT: usize = 4096;
+
+pub fn commands() -> [crate::Command; 1] {
+    [audit()]
+}
+
+/// Starts an audit entry for the running command, with how it was invoked as the arguments.
+pub(crate) fn entry(ctx: Context<'_>) -> AuditEntry {
+    AuditEntry::new(
+        ctx.author().id,
+        &*ctx.command().qualified_name,
+        ctx.invocation_string(),
+    )
+}
+
+/// Records a staff action, mirroring it to the audit channel if one is set.
+pub(crate) async fn record(ctx: Context<'_>, entry: AuditEntry) {
+    let data = ctx.data();
+    if let Err(e) = data.record_audit(&entry).await {
+        tracing::error!(action = %entry.action, "Couldn't record the audit entry: {e}");
+    }
+
+    let channel = data.escape_room.read().audit_channel;
+    let Some(channel) = channel else {
+        return;
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(entry.action.clone())
+        .description(format!(
+            "`{}`",
+            truncate(&entry.arguments, DESCRIPTION_LIMIT - 2)
+        ))
+        .field("By", format!("<@{}>", entry.actor_id), true)
+        .colour(Colour::ORANGE);
+    if let Some(target) = entry.target_id {
+        embed = embed.field("User", format!("<@{target}>"), true);
+    }
+    if let Some(before) = &entry.before {
+        embed = embed.field("Before", truncate(before, FIELD_LIMIT), false);
+    }
+    if let Some(after) = &entry.after {
+        embed = embed.field("After", truncate(after, FIELD_LIMIT), false);
+    }
+
+    if let Err(e) = channel
+        .send_message(
+            ctx.http(),
+            CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await
+    {
+        tracing::error!(action = %entry.action, "Couldn't mirror the audit entry: {e}");
+    }
+}
+
+/// Cuts `value` down to `limit` characters, marking that it was cut.
+fn truncate(value: &str, limit: usize) -> String {
+    if value.chars().count() <= limit {
+        return value.to_owned();
+    }
+
+    let mut truncated: String = value.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Search the staff actions that have been taken.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn audit(
+    ctx: Context<'_>,
+    #[description = "Actions taken by or done to this user."] user: Option<User>,
+    #[description = "Part of the command name."] action: Option<String>,
+    #[description = "How many entries to show. (defaults to 20)"] limit: Option<u32>,
+) -> Result<(), Error> {
+    let entries = ctx
+        .data()
+        .search_audit(
+            user.map(|u| u.id),
+            action.as_deref(),
+            limit.unwrap_or(DEFAULT_LIMIT),
+        )
+        .await?;
+
+    if entries.is_empty() {
+        ctx.say("No matching actions.").await?;
+        return Ok(());
+    }
+
+    let mut content = String::new();
+    for entry in &entries {
+        write!(
+            content,
+            "<t:{}:f> <@{}> `{}`",
+            entry.created_at, entry.actor_id, entry.arguments
+        )
+        .unwrap();
+        if let Some(target) = entry.target_id {
+            write!(content, " on <@{target}>").unwrap();
+        }
+        let before = entry.before.as_deref().map(|b| truncate(b, FIELD_LIMIT));
+        let after = entry.after.as_deref().map(|a| truncate(a, FIELD_LIMIT));
+        match (before, after) {
+            (Some(before), Some(after)) => write!(content, ": {before} -> {after}").unwrap(),
+            (None, Some(after)) => write!(content, ": {after}").unwrap(),
+            (Some(before), None) => write!(content, ": was {before}").unwrap(),
+            (None, None) => {}
+        }
+        content.push('\n');
+    }
+
+    let reply = if content.len() > 2000 {
+        CreateReply::new()
+            .content(format!("{} matching actions.", entries.len()))
+            .attachment(CreateAttachment::bytes(content.into_bytes(), "audit.txt"))
+    } else {
+        CreateReply::new().content(content)
+    };
+
+    ctx.send(reply.allowed_mentions(CreateAllowedMentions::new()))
+        .await?;
+
+    Ok(())
+}
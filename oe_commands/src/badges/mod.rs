@@ -1,8 +1,8 @@
 use crate::badges::users::autocomplete_event;
-use crate::{Context, Error};
+use crate::{Context, Error, audit};
 use ::serenity::all::Attachment;
 use chrono::TimeZone;
-use oe_core::structs::BadgeKind;
+use oe_core::structs::{BadgeKind, Event};
 
 use std::fmt::Write;
 
@@ -154,8 +154,15 @@ pub async fn add_event_prefix(
 
     ctx.data()
         .badges
-        .new_event(ctx.serenity_context(), name, badge_name, attachment_bytes)
+        .new_event(
+            ctx.serenity_context(),
+            name.clone(),
+            badge_name,
+            attachment_bytes,
+        )
         .await?;
+    let ctx = Context::Prefix(ctx);
+    audit::record(ctx, audit::entry(ctx).after(name)).await;
 
     ctx.say("Event added!").await?;
 
@@ -204,13 +211,14 @@ pub async fn add_event(
         .badges
         .new_event_slash(
             ctx.serenity_context(),
-            name,
+            name.clone(),
             badge_name,
             attachment_bytes,
             link,
             event_date_from_string(event_date.as_deref())?,
         )
         .await?;
+    audit::record(ctx, audit::entry(ctx).after(name)).await;
 
     ctx.say("Event added!").await?;
 
@@ -230,7 +238,18 @@ pub async fn update_link(
         return Ok(());
     };
 
-    ctx.data().badges.change_link(id, link).await?;
+    let before = event_by_id(ctx, id)
+        .await?
+        .and_then(|e| e.badge.link)
+        .unwrap_or_else(|| "no link".to_owned());
+    ctx.data().badges.change_link(id, link.clone()).await?;
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .before(before)
+            .after(link.unwrap_or_else(|| "no link".to_owned())),
+    )
+    .await;
 
     ctx.say("link updated!").await?;
 
@@ -250,10 +269,25 @@ pub async fn update_date(
         return Ok(());
     };
 
+    let before = event_by_id(ctx, id)
+        .await?
+        .filter(|e| e.date != 0)
+        .and_then(|e| chrono::DateTime::from_timestamp(e.date, 0))
+        .map_or_else(
+            || "no date".to_owned(),
+            |d| d.format("%Y-%m-%d %H:%M").to_string(),
+        );
     ctx.data()
         .badges
         .change_timestamp(id, event_date_from_string(date.as_deref())?)
         .await?;
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .before(before)
+            .after(date.unwrap_or_else(|| "no date".to_owned())),
+    )
+    .await;
 
     ctx.say("link updated!").await?;
 
@@ -282,10 +316,19 @@ pub async fn update_badge(
 
     let attachment_bytes = attachment.download().await?;
 
+    let before = event_by_id(ctx, id)
+        .await?
+        .map_or_else(|| "no badge".to_owned(), |e| e.badge.discord_name);
     ctx.data()
         .badges
-        .replace_badge(ctx.serenity_context(), id, attachment_bytes, badge_name)
+        .replace_badge(
+            ctx.serenity_context(),
+            id,
+            attachment_bytes,
+            badge_name.clone(),
+        )
         .await?;
+    audit::record(ctx, audit::entry(ctx).before(before).after(badge_name)).await;
 
     ctx.say("badge updated!").await?;
 
@@ -294,6 +337,12 @@ pub async fn update_badge(
 
 // one that replaces the badge.
 
+/// The event as it is now, so updates can record what they changed.
+async fn event_by_id(ctx: Context<'_>, id: u16) -> Result<Option<Event>, Error> {
+    let events = ctx.data().badges.get_events().await?;
+    Ok(events.iter().find(|e| e.id == id).cloned())
+}
+
 #[poise::command(rename = "dbg-cache", prefix_command, guild_only, owners_only)]
 pub async fn dbg_cache(ctx: crate::Context<'_>) -> Result<(), Error> {
     let dbg = format!("{:?}", ctx.data().badges.get_events().await?);
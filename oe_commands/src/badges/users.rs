@@ -1,5 +1,5 @@
 use crate::badges::wrapper::MultipleUserId;
-use crate::{Context, Error};
+use crate::{Context, Error, audit};
 use ::serenity::all::CreateAllowedMentions;
 use oe_core::structs::BadgeKind;
 use poise::serenity_prelude::{self as serenity, User};
@@ -23,10 +23,19 @@ pub async fn add_user_badge(
     #[autocomplete = "autocomplete_event"]
     event_name: String,
 ) -> Result<(), Error> {
+    let before = existing_badge(ctx, user.id, &event_name).await?;
     ctx.data()
         .badges
         .add_user_badge(user.id, &event_name, winner, badge_kind)
         .await?;
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .target(user.id)
+            .before(before)
+            .after(badge_description(&event_name, winner, badge_kind)),
+    )
+    .await;
 
     ctx.say("Done!").await?;
 
@@ -44,10 +53,19 @@ pub async fn add_user_prefix(
     event_name: String,
 ) -> Result<(), Error> {
     for user in users.0 {
+        let before = existing_badge(ctx, user, &event_name).await?;
         ctx.data()
             .badges
             .add_user_badge(user, &event_name, winner, badge_kind)
             .await?;
+        audit::record(
+            ctx,
+            audit::entry(ctx)
+                .target(user)
+                .before(before)
+                .after(badge_description(&event_name, winner, badge_kind)),
+        )
+        .await;
     }
 
     ctx.say("Done!").await?;
@@ -75,6 +93,7 @@ pub async fn remove_user_badge(
         .badges
         .remove_user_badge(user.id, &event_name)
         .await?;
+    audit::record(ctx, audit::entry(ctx).target(user.id).before(event_name)).await;
 
     ctx.say("Done!").await?;
 
@@ -99,6 +118,12 @@ pub async fn remove_user_prefix(
             .is_err()
         {
             failed.push(user);
+        } else {
+            audit::record(
+                ctx,
+                audit::entry(ctx).target(user).before(event_name.as_str()),
+            )
+            .await;
         }
     }
 
@@ -125,6 +150,31 @@ pub async fn remove_user_prefix(
     Ok(())
 }
 
+/// The badge the user already has for the event, so re-adding it records what changed.
+async fn existing_badge(
+    ctx: Context<'_>,
+    user_id: serenity::UserId,
+    event_name: &str,
+) -> Result<String, Error> {
+    let badges = ctx.data().badges.get_user_badges(user_id).await?;
+    Ok(badges
+        .iter()
+        .find(|b| b.event.name.contains(event_name))
+        .map_or_else(
+            || "no badge".to_owned(),
+            |b| badge_description(&b.event.name, b.winner, Some(b.badge_kind)),
+        ))
+}
+
+fn badge_description(event_name: &str, winner: bool, badge_kind: Option<BadgeKind>) -> String {
+    let kind = badge_kind.unwrap_or_default();
+    if winner {
+        format!("{event_name} ({kind:?}, winner)")
+    } else {
+        format!("{event_name} ({kind:?})")
+    }
+}
+
 pub fn commands() -> [crate::Command; 2] {
     let add = poise::Command {
         prefix_action: add_user_prefix().prefix_action,
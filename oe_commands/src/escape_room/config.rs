@@ -0,0 +1,254 @@
+use std::fmt::Write;
+
+use crate::{Context, Error, audit};
+use poise::{
+    ChoiceParameter, CreateReply,
+    serenity_prelude::{ChannelId, CreateAllowedMentions, EditRole, GuildChannel, Role, RoleId},
+};
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum ChannelSetting {
+    #[name = "Error"]
+    Error,
+    #[name = "Analytics"]
+    Analytics,
+    #[name = "Winner"]
+    Winner,
+    #[name = "Review"]
+    Review,
+    #[name = "Audit"]
+    Audit,
+}
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum RoleSetting {
+    #[name = "First winner"]
+    FirstWinner,
+    #[name = "Winner"]
+    Winner,
+}
+
+impl RoleSetting {
+    fn default_name(self) -> &'static str {
+        match self {
+            RoleSetting::FirstWinner => "first-winner",
+            RoleSetting::Winner => "winner",
+        }
+    }
+}
+
+/// View and change the rooms channels and roles.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    guild_only,
+    subcommands("view", "channel", "role", "leaderboard"),
+    subcommand_required
+)]
+pub async fn config(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Shows the channels and roles the room is using.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn view(ctx: Context<'_>) -> Result<(), Error> {
+    let content = {
+        let data = ctx.data();
+        let room = data.escape_room.read();
+
+        let mut content = String::from("**Channels**\n");
+        for (name, channel) in [
+            ("Error", room.error_channel),
+            ("Analytics", room.analytics_channel),
+            ("Winner", room.winners.winner_channel),
+            ("Review", room.review_channel),
+            ("Audit", room.audit_channel),
+        ] {
+            writeln!(content, "{name}: {}", channel_mention(channel)).unwrap();
+        }
+
+        content.push_str("\n**Roles**\n");
+        for (name, role) in [
+            ("First winner", room.winners.first_winner_role),
+            ("Winner", room.winners.winner_role),
+        ] {
+            writeln!(content, "{name}: {}", role_mention(role)).unwrap();
+        }
+
+        content.push_str("\n**Leaderboards**\n");
+        writeln!(
+            content,
+            "Hide question numbers: {}",
+            room.hide_question_numbers
+        )
+        .unwrap();
+        writeln!(
+            content,
+            "Wrong answer penalty: {}",
+            room.wrong_answer_penalty
+                .map_or_else(|| "none".to_owned(), |p| format!("{p} seconds"))
+        )
+        .unwrap();
+
+        content
+    };
+
+    ctx.send(
+        CreateReply::new()
+            .content(content)
+            .allowed_mentions(CreateAllowedMentions::new()),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Sets one of the rooms channels, leave the channel empty to unset it.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn channel(
+    ctx: Context<'_>,
+    #[description = "The channel to change."] setting: ChannelSetting,
+    #[description = "The channel to use."]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let channel_id = channel.map(|c| c.id);
+    let before = {
+        let data = ctx.data();
+        let mut room = data.escape_room.write();
+        let field = match setting {
+            ChannelSetting::Error => &mut room.error_channel,
+            ChannelSetting::Analytics => &mut room.analytics_channel,
+            ChannelSetting::Winner => &mut room.winners.winner_channel,
+            ChannelSetting::Review => &mut room.review_channel,
+            ChannelSetting::Audit => &mut room.audit_channel,
+        };
+        let before = std::mem::replace(field, channel_id);
+        room.write_questions().unwrap();
+        before
+    };
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .before(channel_mention(before))
+            .after(channel_mention(channel_id)),
+    )
+    .await;
+
+    ctx.say(format!(
+        "The {} channel is now {}.",
+        setting.name().to_lowercase(),
+        channel_mention(channel_id)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Sets one of the rooms roles, a new role is made if one isn't given.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn role(
+    ctx: Context<'_>,
+    #[description = "The role to change."] setting: RoleSetting,
+    #[description = "The role to use, leave empty to create one."] role: Option<Role>,
+    #[description = "The name of the role to create."] name: Option<String>,
+) -> Result<(), Error> {
+    let role_id = if let Some(role) = role {
+        role.id
+    } else {
+        let name = name.as_deref().unwrap_or(setting.default_name());
+        ctx.guild_id()
+            .unwrap()
+            .create_role(
+                ctx.http(),
+                EditRole::new()
+                    .name(name)
+                    .mentionable(false)
+                    .hoist(false)
+                    .audit_log_reason("Escape room role created through config."),
+            )
+            .await?
+            .id
+    };
+
+    let before = {
+        let data = ctx.data();
+        let mut room = data.escape_room.write();
+        let field = match setting {
+            RoleSetting::FirstWinner => &mut room.winners.first_winner_role,
+            RoleSetting::Winner => &mut room.winners.winner_role,
+        };
+        let before = field.replace(role_id);
+        room.write_questions().unwrap();
+        before
+    };
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .before(role_mention(before))
+            .after(role_mention(Some(role_id))),
+    )
+    .await;
+
+    ctx.send(
+        CreateReply::new()
+            .content(format!(
+                "The {} role is now <@&{role_id}>.",
+                setting.name().to_lowercase()
+            ))
+            .allowed_mentions(CreateAllowedMentions::new()),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Changes how the public and time leaderboards are shown.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn leaderboard(
+    ctx: Context<'_>,
+    #[description = "Leave question numbers off public leaderboards."]
+    hide_question_numbers: Option<bool>,
+    #[description = "Seconds added to a players time for each wrong answer, 0 to turn off."]
+    wrong_answer_penalty: Option<u64>,
+) -> Result<(), Error> {
+    let (before, after) = {
+        let data = ctx.data();
+        let mut room = data.escape_room.write();
+        let before = leaderboard_settings(room.hide_question_numbers, room.wrong_answer_penalty);
+
+        if let Some(hide) = hide_question_numbers {
+            room.hide_question_numbers = hide;
+        }
+        if let Some(penalty) = wrong_answer_penalty {
+            room.wrong_answer_penalty = (penalty > 0).then_some(penalty);
+        }
+        room.write_questions().unwrap();
+
+        let after = leaderboard_settings(room.hide_question_numbers, room.wrong_answer_penalty);
+        room.live_leaderboard_dirty = true;
+        (before, after)
+    };
+    audit::record(ctx, audit::entry(ctx).before(before).after(after.clone())).await;
+
+    ctx.say(format!("Leaderboards now use: {after}")).await?;
+
+    Ok(())
+}
+
+fn leaderboard_settings(hide_question_numbers: bool, wrong_answer_penalty: Option<u64>) -> String {
+    format!(
+        "hide question numbers {hide_question_numbers}, penalty {}",
+        wrong_answer_penalty.map_or_else(|| "none".to_owned(), |p| format!("{p}s"))
+    )
+}
+
+pub(super) fn channel_mention(channel: Option<ChannelId>) -> String {
+    channel.map_or_else(|| "not set".to_owned(), |c| format!("<#{c}>"))
+}
+
+fn role_mention(role: Option<RoleId>) -> String {
+    role.map_or_else(|| "not set".to_owned(), |r| format!("<@&{r}>"))
+}
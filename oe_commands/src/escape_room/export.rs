@@ -0,0 +1,168 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::{Context, Error};
+use poise::{
+    ChoiceParameter, CreateReply,
+    serenity_prelude::{CreateAttachment, UserId},
+};
+use serde::Serialize;
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum ExportFormat {
+    #[name = "CSV"]
+    Csv,
+    #[name = "JSON"]
+    Json,
+}
+
+#[derive(Serialize)]
+struct Participant {
+    user_id: UserId,
+    /// The question the player is on, past the last question if they finished.
+    progress: usize,
+    started_at: Option<u64>,
+    finished_at: Option<u64>,
+    duration_secs: Option<u64>,
+    attempts: usize,
+    wrong_attempts: usize,
+    /// Always 0, the bot has no hint system yet but the column is kept for spreadsheets.
+    hints_used: usize,
+    placement: Option<usize>,
+    first_winner: bool,
+    winner: bool,
+    timed_out: bool,
+}
+
+/// Exports every players results as a file.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn export(
+    ctx: Context<'_>,
+    #[description = "The file format. (defaults to CSV)"] format: Option<ExportFormat>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let attempts = ctx.data().room_attempts().await?;
+    let mut attempt_counts: HashMap<UserId, (usize, usize)> = HashMap::new();
+    for attempt in &attempts {
+        let (total, wrong) = attempt_counts.entry(attempt.user_id).or_default();
+        *total += 1;
+        if !attempt.correct {
+            *wrong += 1;
+        }
+    }
+
+    let name = ctx.data().room_name();
+    let mut participants: Vec<Participant> = {
+        let data = ctx.data();
+        let room = data.escape_room.read();
+
+        let users: HashSet<UserId> = room
+            .user_progress
+            .keys()
+            .chain(room.start_end_time.keys())
+            .chain(&room.winners.winners)
+            .copied()
+            .collect();
+
+        users
+            .into_iter()
+            .map(|user_id| {
+                let (started_at, end) = room
+                    .start_end_time
+                    .get(&user_id)
+                    .map_or((None, None), |(start, end)| (Some(*start), *end));
+                let (attempts, wrong_attempts) =
+                    attempt_counts.get(&user_id).copied().unwrap_or_default();
+                let placement = room.winners.placement(user_id);
+                // only winners have finished, whatever the end time says.
+                let finished_at = end.filter(|_| placement.is_some());
+
+                Participant {
+                    user_id,
+                    progress: room.user_progress.get(&user_id).copied().unwrap_or(1),
+                    started_at,
+                    finished_at,
+                    duration_secs: started_at
+                        .zip(finished_at)
+                        .map(|(start, end)| end.saturating_sub(start)),
+                    attempts,
+                    wrong_attempts,
+                    hints_used: 0,
+                    placement,
+                    first_winner: room.winners.first_winner == Some(user_id),
+                    winner: placement.is_some(),
+                    timed_out: room.timed_out.contains_key(&user_id),
+                }
+            })
+            .collect()
+    };
+
+    if participants.is_empty() {
+        ctx.say("Nobody has played the escape room yet.").await?;
+        return Ok(());
+    }
+
+    // winners in the order they finished, then everyone else by how far they got.
+    participants.sort_by(|a, b| {
+        match (a.placement, b.placement) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => b.progress.cmp(&a.progress),
+        }
+        .then_with(|| a.started_at.cmp(&b.started_at))
+    });
+
+    let (bytes, file_name) = match format.unwrap_or(ExportFormat::Csv) {
+        ExportFormat::Csv => (to_csv(&participants).into_bytes(), format!("{name}.csv")),
+        ExportFormat::Json => (
+            serde_json::to_vec_pretty(&participants)?,
+            format!("{name}.json"),
+        ),
+    };
+
+    ctx.send(
+        CreateReply::new()
+            .content(format!("Results for {} players.", participants.len()))
+            .attachment(CreateAttachment::bytes(bytes, file_name)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn to_csv(participants: &[Participant]) -> String {
+    let mut csv = String::from(
+        "user_id,progress,started_at,finished_at,duration_secs,attempts,wrong_attempts,hints_used,\
+         placement,first_winner,winner,timed_out\n",
+    );
+
+    for p in participants {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            p.user_id,
+            p.progress,
+            optional(p.started_at),
+            optional(p.finished_at),
+            optional(p.duration_secs),
+            p.attempts,
+            p.wrong_attempts,
+            p.hints_used,
+            optional(p.placement),
+            p.first_winner,
+            p.winner,
+            p.timed_out,
+        )
+        .unwrap();
+    }
+
+    csv
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
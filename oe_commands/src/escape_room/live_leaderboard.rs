@@ -0,0 +1,66 @@
+use crate::{Context, Error, audit};
+use oe_core::structs::LiveLeaderboard;
+use poise::serenity_prelude::GuildChannel;
+
+use super::config::channel_mention;
+
+/// Posts a leaderboard that updates itself, leave the channel empty to stop updating it.
+#[poise::command(
+    rename = "live-leaderboard",
+    slash_command,
+    prefix_command,
+    owners_only,
+    guild_only
+)]
+pub async fn live_leaderboard(
+    ctx: Context<'_>,
+    #[description = "Where to post the leaderboard."]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let channel_id = channel.map(|c| c.id);
+    let before = {
+        let data = ctx.data();
+        let mut room = data.escape_room.write();
+        let before = room.live_leaderboard;
+        room.live_leaderboard = channel_id.map(|channel| LiveLeaderboard {
+            channel,
+            // the same channel keeps editing the message that is already there.
+            message: before
+                .filter(|b| b.channel == channel)
+                .and_then(|b| b.message),
+        });
+        room.write_questions().unwrap();
+        before
+    };
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .before(channel_mention(before.map(|b| b.channel)))
+            .after(channel_mention(channel_id)),
+    )
+    .await;
+
+    // moved somewhere else, so the old one would never be updated again.
+    if let Some(before) = before
+        && let Some(message) = before.message
+        && channel_id.is_some_and(|c| c != before.channel)
+    {
+        let _ = before
+            .channel
+            .delete_message(ctx.http(), message, None)
+            .await;
+    }
+
+    let Some(channel_id) = channel_id else {
+        ctx.say("The live leaderboard won't be updated anymore.")
+            .await?;
+        return Ok(());
+    };
+
+    ctx.data().update_live_leaderboard(ctx.http()).await?;
+    ctx.say(format!("The live leaderboard is now in <#{channel_id}>."))
+        .await?;
+
+    Ok(())
+}
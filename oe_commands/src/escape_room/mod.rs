@@ -1,14 +1,21 @@
+mod config;
+mod export;
+mod live_leaderboard;
+mod reconcile;
+mod reviews;
 mod setup;
 mod setup_channel_manual;
+mod stats;
+mod timeline;
 mod utils;
+mod wrong_answers;
 
-use std::collections::HashMap;
-
-use crate::{Context, Error};
+use crate::{Context, Error, audit};
 use serenity::all::{EditMember, Member, User};
 
-pub fn commands() -> [crate::Command; 6] {
+pub fn commands() -> [crate::Command; 7] {
     [
+        escape_room(),
         setup::setup(),
         setup_channel_manual::send_question(),
         setup::activate(),
@@ -18,6 +25,30 @@ pub fn commands() -> [crate::Command; 6] {
     ]
 }
 
+/// Manage the escape room.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    rename = "escape-room",
+    slash_command,
+    prefix_command,
+    owners_only,
+    guild_only,
+    subcommands(
+        "config::config",
+        "export::export",
+        "live_leaderboard::live_leaderboard",
+        "reconcile::reconcile",
+        "reviews::reviews",
+        "stats::stats",
+        "timeline::timeline",
+        "wrong_answers::wrong_answers"
+    ),
+    subcommand_required
+)]
+pub async fn escape_room(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
 /// Sets the current question of the user.
 #[poise::command(
     rename = "set-question",
@@ -40,14 +71,23 @@ pub async fn set_question(
     };
 
     ctx.defer().await?;
-    {
+    let before = {
         ctx.data()
             .escape_room
             .write()
             .user_progress
-            .insert(member.user.id, question_num as usize);
-    }
+            .insert(member.user.id, question_num as usize)
+    };
+    ctx.data().mark_leaderboard_dirty();
     ctx.data().write_questions().unwrap();
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .target(member.user.id)
+            .before(before.map_or_else(|| "not started".to_owned(), |q| q.to_string()))
+            .after(question_num.to_string()),
+    )
+    .await;
 
     if !modify_permissions.unwrap_or(true) {
         return Ok(());
@@ -120,10 +160,15 @@ pub async fn clear_cooldown(
     ctx: Context<'_>,
     #[description = "The user you are removing the cooldown for."] user: User,
 ) -> Result<(), Error> {
-    {
+    let before = {
         let data = ctx.data();
         let mut room = data.escape_room.write();
         let cooldowns = &mut room.cooldowns;
+        let before = cooldowns
+            .wrong_answer
+            .keys()
+            .filter(|(cooldown_user, _)| *cooldown_user == user.id)
+            .count();
 
         // Collect the entries to remove into a separate vector
         let mut to_remove = Vec::new();
@@ -137,7 +182,15 @@ pub async fn clear_cooldown(
         for item in to_remove {
             cooldowns.wrong_answer.remove(&item);
         }
-    }
+        before
+    };
+    audit::record(
+        ctx,
+        audit::entry(ctx)
+            .target(user.id)
+            .before(format!("{before} cooldowns")),
+    )
+    .await;
     ctx.say("Done!").await?;
 
     Ok(())
@@ -153,12 +206,13 @@ pub async fn clear_cooldown(
     guild_only
 )]
 pub async fn clear_all_cooldowns(ctx: Context<'_>) -> Result<(), Error> {
-    {
+    let before = {
         let data = ctx.data();
         let mut room = data.escape_room.write();
         let cooldowns = &mut room.cooldowns;
-        cooldowns.wrong_answer = HashMap::new();
-    }
+        std::mem::take(&mut cooldowns.wrong_answer).len()
+    };
+    audit::record(ctx, audit::entry(ctx).before(format!("{before} cooldowns"))).await;
     ctx.say("Done!").await?;
 
     Ok(())
@@ -0,0 +1,160 @@
+use std::fmt::Write;
+
+use crate::{Context, Error, audit};
+use poise::{
+    CreateReply,
+    futures_util::StreamExt,
+    serenity_prelude::{CreateAllowedMentions, CreateAttachment, EditMember, Member, RoleId},
+};
+
+struct RoleDiff {
+    member: Member,
+    add: Option<RoleId>,
+    remove: Vec<RoleId>,
+}
+
+/// Compares everyones question roles against their progress, fixing any that are wrong.
+///
+/// Players that ran out of time or have a role change waiting to be retried are left alone.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn reconcile(
+    ctx: Context<'_>,
+    #[description = "Only show what would change. (defaults to true)"] dry_run: Option<bool>,
+) -> Result<(), Error> {
+    let dry_run = dry_run.unwrap_or(true);
+    let guild_id = ctx.guild_id().unwrap();
+    ctx.defer().await?;
+
+    let managed_roles: Vec<RoleId> = {
+        let data = ctx.data();
+        let room = data.escape_room.read();
+        room.questions
+            .iter()
+            .filter_map(|q| q.role_id)
+            .chain(room.winners.roles())
+            .collect()
+    };
+
+    let mut diffs = Vec::new();
+    let mut members = std::pin::pin!(guild_id.members_iter(ctx.http()));
+    while let Some(member) = members.next().await {
+        let member = member?;
+        if member.user.bot() {
+            continue;
+        }
+
+        // their question role was swapped for the time's up role on purpose, and queued
+        // changes are already being retried.
+        let data = ctx.data();
+        let timed_out = data
+            .escape_room
+            .read()
+            .timed_out
+            .contains_key(&member.user.id);
+        if timed_out || data.has_pending_role_change(member.user.id) {
+            continue;
+        }
+
+        let expected = data.expected_role(member.user.id);
+        let add = expected.filter(|role| !member.roles.contains(role));
+        let remove: Vec<RoleId> = member
+            .roles
+            .iter()
+            .filter(|&&role| managed_roles.contains(&role) && Some(role) != expected)
+            .copied()
+            .collect();
+
+        if add.is_some() || !remove.is_empty() {
+            diffs.push(RoleDiff {
+                member,
+                add,
+                remove,
+            });
+        }
+    }
+
+    if diffs.is_empty() {
+        ctx.say("Everyones roles match their progress!").await?;
+        return Ok(());
+    }
+
+    let mut content = String::new();
+    let mut failed = 0;
+    for diff in &mut diffs {
+        write!(content, "<@{}>:", diff.member.user.id).unwrap();
+        if let Some(add) = diff.add {
+            write!(content, " +<@&{add}>").unwrap();
+        }
+        for remove in &diff.remove {
+            write!(content, " -<@&{remove}>").unwrap();
+        }
+        content.push('\n');
+
+        if dry_run {
+            continue;
+        }
+
+        let mut roles = diff.member.roles.to_vec();
+        roles.retain(|role| !diff.remove.contains(role));
+        roles.extend(diff.add);
+
+        if diff
+            .member
+            .edit(
+                ctx.http(),
+                EditMember::new()
+                    .roles(roles)
+                    .audit_log_reason("Reconciling escape room roles."),
+            )
+            .await
+            .is_err()
+        {
+            failed += 1;
+        }
+    }
+
+    let header = if dry_run {
+        format!(
+            "{} members have roles that don't match their progress:",
+            diffs.len()
+        )
+    } else {
+        format!(
+            "Fixed {} of {} members with roles that didn't match their progress:",
+            diffs.len() - failed,
+            diffs.len()
+        )
+    };
+
+    let mentions = CreateAllowedMentions::new()
+        .all_roles(false)
+        .all_users(false)
+        .everyone(false);
+
+    let reply = if header.len() + content.len() > 1999 {
+        CreateReply::new()
+            .content(header)
+            .attachment(CreateAttachment::bytes(
+                content.into_bytes(),
+                "reconcile.txt",
+            ))
+    } else {
+        CreateReply::new().content(format!("{header}\n{content}"))
+    };
+
+    ctx.send(reply.allowed_mentions(mentions)).await?;
+
+    if !dry_run {
+        audit::record(
+            ctx,
+            audit::entry(ctx).after(format!(
+                "fixed {} of {} members",
+                diffs.len() - failed,
+                diffs.len()
+            )),
+        )
+        .await;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::fmt::Write;
+
+use crate::{Context, Error};
+use poise::{CreateReply, serenity_prelude::CreateAllowedMentions};
+
+/// Lists the submissions still waiting on a review.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn reviews(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+
+    let content = {
+        let data = ctx.data();
+        let room = data.escape_room.read();
+
+        if room.reviews.is_empty() {
+            "There are no submissions waiting to be reviewed.".to_string()
+        } else {
+            let mut content = String::from("**Pending reviews**\n");
+            for review in &room.reviews {
+                writeln!(
+                    content,
+                    "<@{}> question {} submitted <t:{}:R>: \
+                     https://discord.com/channels/{guild_id}/{}/{}",
+                    review.user_id,
+                    review.question + 1,
+                    review.submitted_at,
+                    review.channel,
+                    review.message,
+                )
+                .unwrap();
+            }
+            content
+        }
+    };
+
+    ctx.send(
+        CreateReply::new()
+            .content(content)
+            .allowed_mentions(CreateAllowedMentions::new()),
+    )
+    .await?;
+
+    Ok(())
+}
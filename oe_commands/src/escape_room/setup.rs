@@ -1,15 +1,15 @@
 use aformat::aformat;
-use std::borrow::Cow;
 use std::{fmt::Write, sync::Arc};
 
+use crate::audit;
 use crate::checks::not_active;
 use crate::escape_room::utils::activate::unlock_first_channel;
 use crate::{Context, Data, Error};
-use oe_core::structs::Question;
+use oe_core::messages::send_question;
+use oe_core::structs::{Question, RoomMode};
 use poise::serenity_prelude::{
-    self as serenity, ChannelId, ChannelType, CreateAttachment, CreateButton, CreateMessage,
-    GuildChannel, GuildId, PermissionOverwrite, PermissionOverwriteType, Permissions, RoleId,
-    UserId,
+    self as serenity, ChannelId, ChannelType, GuildChannel, GuildId, PermissionOverwrite,
+    PermissionOverwriteType, Permissions, RoleId, UserId,
 };
 
 /// Start the escape room!
@@ -25,12 +25,18 @@ pub async fn activate(
     #[description = "Start the escape room!"] activate: Option<bool>,
 ) -> Result<(), Error> {
     if let Some(activate) = activate {
+        let before = ctx.data().get_status();
         if activate {
             match unlock_first_channel(ctx).await {
                 Ok(()) => {
                     ctx.say("Activating the escape room and all interactions, Good luck!")
                         .await?;
                     ctx.data().set_status(true);
+                    audit::record(
+                        ctx,
+                        audit::entry(ctx).before(before.to_string()).after("true"),
+                    )
+                    .await;
                 }
                 Err(e) => {
                     ctx.say(e.to_string()).await?;
@@ -42,6 +48,11 @@ pub async fn activate(
 
         ctx.data().set_status(false);
         ctx.say("Deactivated the escape room!").await?;
+        audit::record(
+            ctx,
+            audit::entry(ctx).before(before.to_string()).after("false"),
+        )
+        .await;
         return Ok(());
     }
 
@@ -100,7 +111,11 @@ pub async fn setup(
             .push_str("I don't have manage roles, I need this on my user, not on the category!\n");
     }
 
-    let required = get_required_bot_perms();
+    let mut required = get_required_bot_perms();
+    if ctx.data().escape_room.read().room_mode == RoomMode::Threads {
+        required |= get_thread_perms();
+    }
+
     let missing_permissions = required & !permissions;
     if missing_permissions.bits() != 0 {
         write!(
@@ -136,7 +151,19 @@ pub async fn setup(
         }
     }
 
-    setup_channels(ctx, ctx.guild_id().unwrap(), category.id, bot_id).await
+    let media_problems = media_problems(&ctx.data());
+    if !media_problems.is_empty() {
+        ctx.say(format!(
+            "Some questions have media that can't be sent:\n{media_problems}"
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    setup_channels(ctx, ctx.guild_id().unwrap(), category.id, bot_id).await?;
+    audit::record(ctx, audit::entry(ctx)).await;
+
+    Ok(())
 }
 
 fn check_setup(data: &Arc<Data>) -> (bool, bool) {
@@ -146,14 +173,31 @@ fn check_setup(data: &Arc<Data>) -> (bool, bool) {
         .iter()
         .any(|q| q.channel.is_some() || q.custom_id.is_some());
 
-    let unanswerable = room
-        .questions
-        .iter()
-        .any(|q| q.parts.is_empty() || q.parts.iter().any(|p| p.answers.is_empty()));
+    let unanswerable = room.questions.iter().any(|q| !q.is_answerable());
 
     (setup, unanswerable)
 }
 
+/// Lists any missing files or media that goes over discords limits, one question per line.
+fn media_problems(data: &Arc<Data>) -> String {
+    let room = data.escape_room.read();
+    let mut problems = String::new();
+    for (i, question) in room.questions.iter().enumerate() {
+        let question_problems = question.media_problems();
+        if !question_problems.is_empty() {
+            writeln!(
+                problems,
+                "Question {}: {}",
+                i + 1,
+                question_problems.join(", ")
+            )
+            .unwrap();
+        }
+    }
+
+    problems
+}
+
 #[allow(clippy::too_many_lines)]
 async fn setup_channels(
     ctx: Context<'_>,
@@ -161,21 +205,24 @@ async fn setup_channels(
     category_id: ChannelId,
     bot_id: UserId,
 ) -> Result<(), Error> {
-    let (mut questions, first_winner_role, winner_role) = {
+    let (mut questions, winner_roles, roles_configured, room_mode) = {
         let data = ctx.data();
         let room = data.escape_room.read();
         (
             room.questions.clone(),
-            room.winners.first_winner_role,
-            room.winners.winner_role,
+            room.winners.roles(),
+            room.winners.roles_configured(),
+            room.room_mode,
         )
     };
+    // every question after the first is sent to each players own thread.
+    let threads = room_mode == RoomMode::Threads;
 
-    let (Some(first_winner_role), Some(winner_role)) = (first_winner_role, winner_role) else {
+    if !roles_configured {
         ctx.say("winner roles have not been configured correctly!")
             .await?;
         return Ok(());
-    };
+    }
 
     if questions.is_empty() {
         ctx.say("There isn't any questions!").await?;
@@ -186,22 +233,24 @@ async fn setup_channels(
 
     let ctx_id = ctx.id();
 
-    // we don't need a role 1, so we can skip this.
-    let mut index = 2_u16;
-    for question in questions.iter_mut().skip(1) {
-        let name = aformat!("question-{index}");
-        let role = guild_id
-            .create_role(
-                ctx.http(),
-                serenity::EditRole::new()
-                    .name(name.as_str())
-                    .mentionable(false)
-                    .hoist(false),
-            )
-            .await?;
+    // we don't need a role 1, so we can skip this, threads are private so don't need any.
+    if !threads {
+        let mut index = 2_u16;
+        for question in questions.iter_mut().skip(1) {
+            let name = aformat!("question-{index}");
+            let role = guild_id
+                .create_role(
+                    ctx.http(),
+                    serenity::EditRole::new()
+                        .name(name.as_str())
+                        .mentionable(false)
+                        .hoist(false),
+                )
+                .await?;
 
-        question.role_id = Some(role.id);
-        index += 1;
+            question.role_id = Some(role.id);
+            index += 1;
+        }
     }
 
     #[allow(clippy::cast_possible_truncation)]
@@ -209,8 +258,16 @@ async fn setup_channels(
     let mut index = 1_u16;
 
     let first_permissions =
-        get_first_question_overrides(&questions, guild_id, bot_id, first_winner_role, winner_role);
+        get_first_question_overrides(&questions, guild_id, bot_id, &winner_roles);
     for question in &mut questions {
+        let custom_id = aformat!("{ctx_id}_{}", index - 1);
+        question.custom_id = Some(custom_id);
+
+        if threads && index != 1 {
+            index += 1;
+            continue;
+        }
+
         let channel_name = aformat!("question-{index}");
         let mut builder = serenity::CreateChannel::new(channel_name.as_str())
             .category(category_id)
@@ -226,10 +283,7 @@ async fn setup_channels(
 
         let channel = guild_id.create_channel(ctx.http(), builder).await?;
 
-        let custom_id = aformat!("{ctx_id}_{}", index - 1);
-
         // modify the question.
-        question.custom_id = Some(custom_id);
         question.channel = Some(channel.id);
 
         send_messages(ctx, channel.id, question, index).await?;
@@ -239,7 +293,7 @@ async fn setup_channels(
     }
 
     // create winners room.
-    let winner_perms = get_winner_overrides(guild_id, bot_id, first_winner_role, winner_role);
+    let winner_perms = get_winner_overrides(guild_id, bot_id, &winner_roles);
     let builder = serenity::CreateChannel::new("the-end")
         .permissions(&winner_perms)
         .category(category_id)
@@ -267,6 +321,13 @@ fn get_required_bot_perms() -> Permissions {
     get_deny_perms() | Permissions::MANAGE_CHANNELS
 }
 
+/// What the bot needs on top of [`get_required_bot_perms`] to make a thread for each player.
+fn get_thread_perms() -> Permissions {
+    Permissions::CREATE_PRIVATE_THREADS
+        | Permissions::MANAGE_THREADS
+        | Permissions::SEND_MESSAGES_IN_THREADS
+}
+
 /// These permissions are to be removed from users, but the bot needs them to do that.
 fn get_deny_perms() -> Permissions {
     Permissions::VIEW_CHANNEL
@@ -305,12 +366,11 @@ fn get_perm_overwrites(
 fn get_winner_overrides(
     guild_id: GuildId,
     bot_id: UserId,
-    first_winner: RoleId,
-    winner: RoleId,
-) -> [PermissionOverwrite; 4] {
+    winner_roles: &[RoleId],
+) -> Vec<PermissionOverwrite> {
     let deny = get_deny_perms();
     let bot_allow = get_required_bot_perms();
-    [
+    let mut overwrites = vec![
         PermissionOverwrite {
             allow: Permissions::empty(),
             deny,
@@ -321,38 +381,33 @@ fn get_winner_overrides(
             deny: Permissions::empty(),
             kind: PermissionOverwriteType::Member(bot_id),
         },
-        PermissionOverwrite {
-            allow: deny,
-            deny: Permissions::empty(),
-            kind: PermissionOverwriteType::Role(first_winner),
-        },
-        PermissionOverwrite {
-            allow: deny,
-            deny: Permissions::empty(),
-            kind: PermissionOverwriteType::Role(winner),
-        },
-    ]
+    ];
+    overwrites.extend(winner_roles.iter().map(|role| PermissionOverwrite {
+        allow: deny,
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Role(*role),
+    }));
+
+    overwrites
 }
 
 fn get_first_question_overrides(
     questions: &Vec<Question>,
     guild_id: GuildId,
     bot_id: UserId,
-    first_winner: RoleId,
-    winner: RoleId,
+    winner_roles: &[RoleId],
 ) -> Vec<PermissionOverwrite> {
     let deny = get_deny_perms();
     let bot_allow = get_required_bot_perms();
 
-    let mut role_overwrites = Vec::with_capacity(questions.len() + 1);
-    for q in questions {
-        if let Some(role) = q.role_id {
-            role_overwrites.push(PermissionOverwrite {
-                allow: Permissions::empty(),
-                deny,
-                kind: PermissionOverwriteType::Role(role),
-            });
-        }
+    let mut role_overwrites = Vec::with_capacity(questions.len() + winner_roles.len() + 2);
+    let roles = questions.iter().filter_map(|q| q.role_id);
+    for role in roles.chain(winner_roles.iter().copied()) {
+        role_overwrites.push(PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny,
+            kind: PermissionOverwriteType::Role(role),
+        });
     }
     role_overwrites.extend([
         PermissionOverwrite {
@@ -360,16 +415,6 @@ fn get_first_question_overrides(
             deny,
             kind: PermissionOverwriteType::Role(guild_id.get().into()),
         },
-        PermissionOverwrite {
-            allow: Permissions::empty(),
-            deny,
-            kind: PermissionOverwriteType::Role(first_winner),
-        },
-        PermissionOverwrite {
-            allow: Permissions::empty(),
-            deny,
-            kind: PermissionOverwriteType::Role(winner),
-        },
         PermissionOverwrite {
             allow: bot_allow, // the bot needs these perms.
             deny: Permissions::empty(),
@@ -386,57 +431,7 @@ pub async fn send_messages_core(
     question: &Question,
     question_number: u16,
 ) -> Result<(), Error> {
-    let mut embed = serenity::all::CreateEmbed::new()
-        .title(format!("Question #{question_number}"))
-        .description(question.content.clone())
-        .colour(serenity::all::Colour::BLUE);
-
-    if let Some(url) = &question.image_path {
-        // shouldn't really unwrap here but w/e, needs an entire rewrite anyway.
-        let name = url.strip_prefix("files/").unwrap();
-        embed = embed.attachment(name);
-    }
-
-    let mut builder = CreateMessage::new().embed(embed);
-
-    if let Some(url) = &question.image_path {
-        let attachment = CreateAttachment::path(url).await;
-        match attachment {
-            Ok(attachment) => {
-                builder = builder.add_file(attachment);
-            }
-            _ => {
-                return Err(format!("Could not set image for question {question_number}").into());
-            }
-        }
-    }
-
-    if let Some(custom_id) = question.custom_id {
-        let components = vec![serenity::all::CreateActionRow::Buttons(Cow::Owned(vec![
-            CreateButton::new(custom_id.as_str()).label("Submit Answer"),
-        ]))];
-
-        builder = builder.components(components);
-        channel_id.send_message(ctx.http(), builder).await?;
-    } else {
-        channel_id.send_message(ctx.http(), builder).await?;
-    }
-
-    if let Some(url) = &question.attachment_path {
-        let attachment = CreateAttachment::path(url).await;
-        match attachment {
-            Ok(attachment) => {
-                channel_id
-                    .send_message(ctx.http(), CreateMessage::new().add_file(attachment))
-                    .await?;
-            }
-            _ => {
-                return Err(
-                    format!("Could not set attachment for question {question_number}").into(),
-                );
-            }
-        }
-    }
+    send_question(ctx.http(), channel_id, question, question_number).await?;
     Ok(())
 }
 
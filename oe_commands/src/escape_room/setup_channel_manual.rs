@@ -1,6 +1,6 @@
 use poise::CreateReply;
 
-use crate::{Context, Error, escape_room::setup::maybe_send_messages};
+use crate::{Context, Error, audit, escape_room::setup::maybe_send_messages};
 
 /// Resends a channels question.
 #[poise::command(
@@ -35,11 +35,13 @@ pub async fn send_question(
     };
 
     maybe_send_messages(ctx, ctx.channel_id(), &question, question_number).await?;
-    println!(
-        "{}: manually invoked question {question_number} in {}",
-        ctx.author().name,
-        ctx.channel_id()
+    tracing::info!(
+        user_id = %ctx.author().id,
+        channel_id = %ctx.channel_id(),
+        question = question_number,
+        "Question manually invoked."
     );
+    audit::record(ctx, audit::entry(ctx)).await;
     ctx.send(CreateReply::new().content("Done!").ephemeral(true))
         .await?;
 
@@ -0,0 +1,179 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::{Context, Error};
+use oe_core::templates::format_duration;
+use poise::{
+    CreateReply,
+    serenity_prelude::{Colour, CreateAttachment, CreateEmbed, UserId},
+};
+
+struct QuestionStats {
+    attempts: usize,
+    players: usize,
+    correct: usize,
+    /// Players that got to the question at some point.
+    reached: usize,
+    solved: usize,
+    /// Seconds between reaching and solving the question, sorted.
+    times: Vec<u64>,
+}
+
+impl QuestionStats {
+    #[allow(clippy::cast_precision_loss)]
+    fn success_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.correct as f64 / self.attempts as f64 * 100.0
+    }
+
+    fn drop_off(&self) -> usize {
+        self.reached.saturating_sub(self.solved)
+    }
+}
+
+/// Shows how players are getting on with each question.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let attempts = ctx.data().room_attempts().await?;
+    let (question_count, progress, starts) = {
+        let data = ctx.data();
+        let room = data.escape_room.read();
+        let starts: HashMap<UserId, u64> = room
+            .start_end_time
+            .iter()
+            .map(|(user, (start, _))| (*user, *start))
+            .collect();
+        // players on the first question might only have a start time or some attempts.
+        let mut progress: HashMap<UserId, usize> = starts
+            .keys()
+            .chain(attempts.iter().map(|a| &a.user_id))
+            .map(|user| (*user, 1))
+            .collect();
+        progress.extend(&room.user_progress);
+        for winner in &room.winners.winners {
+            progress.insert(*winner, room.questions.len() + 1);
+        }
+        (room.questions.len(), progress, starts)
+    };
+
+    // when each player first solved each question.
+    let mut solved_at: HashMap<(UserId, u16), u64> = HashMap::new();
+    for attempt in attempts.iter().filter(|a| a.correct) {
+        solved_at
+            .entry((attempt.user_id, attempt.question))
+            .or_insert(attempt.attempted_at);
+    }
+
+    let mut stats = Vec::with_capacity(question_count);
+    for number in 1..=question_count {
+        #[allow(clippy::cast_possible_truncation)]
+        let question = number as u16;
+        let question_attempts: Vec<_> =
+            attempts.iter().filter(|a| a.question == question).collect();
+
+        let mut times: Vec<u64> = solved_at
+            .iter()
+            .filter(|((_, q), _)| *q == question)
+            .filter_map(|((user, _), end)| {
+                let start = if question == 1 {
+                    starts.get(user)
+                } else {
+                    solved_at.get(&(*user, question - 1))
+                }?;
+                Some(end.saturating_sub(*start))
+            })
+            .collect();
+        times.sort_unstable();
+
+        stats.push(QuestionStats {
+            attempts: question_attempts.len(),
+            players: question_attempts
+                .iter()
+                .map(|a| a.user_id)
+                .collect::<HashSet<_>>()
+                .len(),
+            correct: question_attempts.iter().filter(|a| a.correct).count(),
+            reached: progress.values().filter(|p| **p >= number).count(),
+            solved: progress.values().filter(|p| **p > number).count(),
+            times,
+        });
+    }
+
+    if stats.is_empty() {
+        ctx.say("There aren't any questions!").await?;
+        return Ok(());
+    }
+
+    let mut description = String::new();
+    let mut csv = String::from(
+        "question,attempts,players,correct,success_rate,reached,solved,drop_off,median_secs,\
+         p90_secs\n",
+    );
+    for (i, s) in stats.iter().enumerate() {
+        let median = percentile(&s.times, 50);
+        let p90 = percentile(&s.times, 90);
+
+        writeln!(
+            description,
+            "**Q{}** {} attempts, {} players, {:.0}% correct, median {}, p90 {}, {} stuck",
+            i + 1,
+            s.attempts,
+            s.players,
+            s.success_rate(),
+            median.map_or_else(|| "-".to_owned(), format_duration),
+            p90.map_or_else(|| "-".to_owned(), format_duration),
+            s.drop_off(),
+        )
+        .unwrap();
+
+        writeln!(
+            csv,
+            "{},{},{},{},{:.2},{},{},{},{},{}",
+            i + 1,
+            s.attempts,
+            s.players,
+            s.correct,
+            s.success_rate(),
+            s.reached,
+            s.solved,
+            s.drop_off(),
+            median.map(|m| m.to_string()).unwrap_or_default(),
+            p90.map(|p| p.to_string()).unwrap_or_default(),
+        )
+        .unwrap();
+    }
+
+    if description.len() > 4096 {
+        description = "Too many questions to show here, see the attached CSV.".to_owned();
+    }
+
+    let embed = CreateEmbed::new()
+        .title(format!("{} stats", ctx.data().room_name()))
+        .description(description)
+        .colour(Colour::BLUE);
+
+    ctx.send(
+        CreateReply::new()
+            .embed(embed)
+            .attachment(CreateAttachment::bytes(csv.into_bytes(), "stats.csv")),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Nearest rank percentile of an already sorted list.
+fn percentile(sorted: &[u64], percent: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
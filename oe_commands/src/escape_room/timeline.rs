@@ -0,0 +1,163 @@
+use std::fmt::Write;
+
+use crate::{Context, Error};
+use oe_core::templates::format_duration;
+use poise::{
+    CreateReply,
+    serenity_prelude::{CreateAllowedMentions, CreateAttachment, User},
+};
+
+enum Entry {
+    Started,
+    Reached(u16),
+    Attempt {
+        question: u16,
+        correct: bool,
+        cooldown_secs: u64,
+    },
+    RoleChange {
+        description: String,
+        succeeded: bool,
+    },
+}
+
+/// Shows everything a player has done in the escape room, in order.
+#[poise::command(slash_command, prefix_command, owners_only, guild_only)]
+pub async fn timeline(
+    ctx: Context<'_>,
+    #[description = "The player to look at."] user: User,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let data = ctx.data();
+    let attempts = data.user_attempts(user.id).await?;
+    let splits = data.user_splits(user.id).await?;
+    let role_changes = data.user_role_changes(user.id).await?;
+    let (start, question_count) = {
+        let room = data.escape_room.read();
+        (
+            room.start_end_time.get(&user.id).map(|(start, _)| *start),
+            room.questions.len(),
+        )
+    };
+
+    let mut entries: Vec<(u64, Entry)> = Vec::new();
+    entries.extend(start.map(|start| (start, Entry::Started)));
+    entries.extend(
+        splits
+            .iter()
+            .map(|s| (s.reached_at, Entry::Reached(s.question))),
+    );
+    entries.extend(attempts.iter().map(|a| {
+        (
+            a.attempted_at,
+            Entry::Attempt {
+                question: a.question,
+                correct: a.correct,
+                cooldown_secs: a.cooldown_secs,
+            },
+        )
+    }));
+    entries.extend(role_changes.iter().map(|c| {
+        let mut description = String::new();
+        if let Some(added) = c.added {
+            write!(description, "+<@&{added}> ").unwrap();
+        }
+        if let Some(removed) = c.removed {
+            write!(description, "-<@&{removed}>").unwrap();
+        }
+        (
+            c.changed_at,
+            Entry::RoleChange {
+                description,
+                succeeded: c.succeeded,
+            },
+        )
+    }));
+    // stable, so an attempt stays before the split it caused.
+    entries.sort_by_key(|(at, _)| *at);
+
+    if entries.is_empty() {
+        ctx.say(format!("<@{}> hasn't started the escape room.", user.id))
+            .await?;
+        return Ok(());
+    }
+
+    let mut content = format!("**Timeline for <@{}>**\n", user.id);
+    let mut last_split = start;
+    for (at, entry) in &entries {
+        write!(content, "<t:{at}:T> ").unwrap();
+        match entry {
+            Entry::Started => content.push_str("Started question 1"),
+            Entry::Reached(question) => {
+                let split = last_split
+                    .map(|last| format!(" ({})", format_duration(at.saturating_sub(last))))
+                    .unwrap_or_default();
+                last_split = Some(*at);
+
+                if usize::from(*question) > question_count {
+                    write!(content, "Finished{split}").unwrap();
+                } else {
+                    write!(content, "Reached question {question}{split}").unwrap();
+                }
+            }
+            Entry::Attempt {
+                question,
+                correct,
+                cooldown_secs,
+            } => {
+                if *correct {
+                    write!(content, "Answered question {question} correctly").unwrap();
+                } else {
+                    write!(
+                        content,
+                        "Answered question {question} wrong, {} cooldown",
+                        format_duration(*cooldown_secs)
+                    )
+                    .unwrap();
+                }
+            }
+            Entry::RoleChange {
+                description,
+                succeeded,
+            } => {
+                let result = if *succeeded { "" } else { " (failed)" };
+                write!(content, "Roles {description}{result}").unwrap();
+            }
+        }
+        content.push('\n');
+    }
+
+    content.push_str("\n**Per question**\n");
+    for question in 1..=question_count {
+        #[allow(clippy::cast_possible_truncation)]
+        let question = question as u16;
+        let question_attempts = attempts.iter().filter(|a| a.question == question);
+        let total = question_attempts.clone().count();
+        if total == 0 {
+            continue;
+        }
+        let cooldowns = question_attempts.filter(|a| a.cooldown_secs > 0).count();
+        writeln!(
+            content,
+            "Question {question}: {total} attempts, {cooldowns} cooldowns"
+        )
+        .unwrap();
+    }
+
+    let reply = if content.len() > 2000 {
+        CreateReply::new()
+            .content(format!("Timeline for <@{}>", user.id))
+            .attachment(CreateAttachment::bytes(
+                content.into_bytes(),
+                "timeline.txt",
+            ))
+    } else {
+        CreateReply::new().content(content)
+    };
+
+    ctx.send(reply.allowed_mentions(CreateAllowedMentions::new()))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use crate::{Context, Error};
+use oe_core::structs::QuestionPart;
+use poise::{
+    CreateReply,
+    serenity_prelude::{Colour, CreateEmbed},
+};
+
+/// The most wrong answers shown for each part.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Shows the most common wrong answers to a question, and any that are close to being right.
+#[poise::command(
+    rename = "wrong-answers",
+    slash_command,
+    prefix_command,
+    owners_only,
+    guild_only
+)]
+pub async fn wrong_answers(
+    ctx: Context<'_>,
+    #[description = "The question number."] question: u16,
+    #[description = "Answers shown for each part. (defaults to 10)"] limit: Option<usize>,
+) -> Result<(), Error> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let parts = {
+        let data = ctx.data();
+        let room = data.escape_room.read();
+        room.questions
+            .get(usize::from(question).wrapping_sub(1))
+            .map(|q| q.parts.clone())
+    };
+
+    let Some(parts) = parts else {
+        ctx.say("That question doesn't exist!").await?;
+        return Ok(());
+    };
+
+    ctx.defer().await?;
+    let attempts = ctx.data().room_attempts().await?;
+
+    let mut counts: Vec<HashMap<String, usize>> = vec![HashMap::new(); parts.len()];
+    for attempt in attempts
+        .iter()
+        .filter(|a| a.question == question && !a.correct)
+    {
+        for ((answer, part), counts) in attempt.answers.iter().zip(&parts).zip(&mut counts) {
+            // other parts of the attempt could have been right.
+            if is_accepted(part, answer) {
+                continue;
+            }
+            *counts.entry(normalise(answer)).or_default() += 1;
+        }
+    }
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("Wrong answers for question {question}"))
+        .colour(Colour::RED);
+
+    for (i, (part, counts)) in parts.iter().zip(counts).enumerate() {
+        let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+        ranked.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut value = String::new();
+        for (answer, count) in ranked.into_iter().take(limit) {
+            let line = match closest_answer(part, &answer) {
+                Some((accepted, distance)) => format!(
+                    "`{answer}` x{count} (close to `{accepted}`, {distance} edit{})\n",
+                    if distance == 1 { "" } else { "s" }
+                ),
+                None => format!("`{answer}` x{count}\n"),
+            };
+
+            // embed fields can't be longer than this.
+            if value.len() + line.len() > 1024 {
+                break;
+            }
+            value.push_str(&line);
+        }
+
+        if value.is_empty() {
+            value.push_str("No wrong answers yet.");
+        }
+
+        let mut name = format!("Part {}: {}", i + 1, part.content);
+        if name.len() > 256 {
+            name = format!("Part {}", i + 1);
+        }
+        embed = embed.field(name, value, false);
+    }
+
+    ctx.send(CreateReply::new().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Lowercases, trims and collapses whitespace so small differences are counted together.
+fn normalise(answer: &str) -> String {
+    let mut normalised = String::with_capacity(answer.len());
+    for word in answer.split_whitespace() {
+        if !normalised.is_empty() {
+            normalised.push(' ');
+        }
+        normalised.push_str(&word.to_lowercase());
+    }
+    normalised
+}
+
+/// Mirrors how answers are checked when they are submitted.
+fn is_accepted(part: &QuestionPart, answer: &str) -> bool {
+    part.answers.iter().any(|a| a.eq_ignore_ascii_case(answer))
+        || part.regex_answers.iter().any(|r| r.is_match(answer))
+}
+
+/// The nearest accepted answer, if it's close enough to be a likely typo or alternate spelling.
+fn closest_answer<'a>(part: &'a QuestionPart, answer: &str) -> Option<(&'a str, usize)> {
+    part.answers
+        .iter()
+        .map(|accepted| (accepted.as_str(), levenshtein(&normalise(accepted), answer)))
+        .filter(|(accepted, distance)| *distance <= (accepted.chars().count() / 4).max(1))
+        .min_by_key(|(_, distance)| *distance)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
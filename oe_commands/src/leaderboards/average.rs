@@ -0,0 +1,89 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{Context, Error};
+use oe_core::templates::format_duration;
+use poise::{ChoiceParameter, serenity_prelude::UserId};
+
+use super::{pages, paginate};
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum AverageBy {
+    #[name = "Question"]
+    Question,
+    #[name = "Player"]
+    Player,
+}
+
+/// Average and fastest times for each question, or players ranked by their average.
+#[poise::command(slash_command, owners_only, guild_only)]
+pub async fn average(
+    ctx: Context<'_>,
+    #[description = "Group by question or player. (defaults to question)"] by: Option<AverageBy>,
+) -> Result<(), Error> {
+    let times = ctx.data().solve_times().await?;
+
+    let (title, lines) = match by.unwrap_or(AverageBy::Question) {
+        AverageBy::Question => ("Times for each question", by_question(&times)),
+        AverageBy::Player => ("Users sorted by average time", by_player(&times)),
+    };
+
+    paginate(ctx, title, &pages(&lines)).await
+}
+
+fn by_question(times: &HashMap<(UserId, u16), u64>) -> Vec<String> {
+    let mut questions: BTreeMap<u16, Vec<(UserId, u64)>> = BTreeMap::new();
+    for (&(user_id, question), &time) in times {
+        questions.entry(question).or_default().push((user_id, time));
+    }
+
+    questions
+        .into_iter()
+        .filter_map(|(question, times)| {
+            let (fastest_user, fastest) = times
+                .iter()
+                .min_by_key(|(user_id, time)| (*time, *user_id))
+                .copied()?;
+
+            Some(format!(
+                "**Question {question}**: {} average, fastest {} by <@{fastest_user}> ({} solves)",
+                format_duration(average_of(times.iter().map(|(_, t)| *t))),
+                format_duration(fastest),
+                times.len()
+            ))
+        })
+        .collect()
+}
+
+fn by_player(times: &HashMap<(UserId, u16), u64>) -> Vec<String> {
+    let mut players: HashMap<UserId, Vec<u64>> = HashMap::new();
+    for (&(user_id, _), &time) in times {
+        players.entry(user_id).or_default().push(time);
+    }
+
+    let mut averages: Vec<(UserId, u64, usize)> = players
+        .into_iter()
+        .map(|(user_id, times)| (user_id, average_of(times.iter().copied()), times.len()))
+        .collect();
+    // more questions solved breaks ties.
+    averages.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| b.2.cmp(&a.2)));
+
+    averages
+        .into_iter()
+        .enumerate()
+        .map(|(i, (user_id, average, solved))| {
+            format!(
+                "`{}.` <@{user_id}>: {} average over {solved} questions",
+                i + 1,
+                format_duration(average)
+            )
+        })
+        .collect()
+}
+
+fn average_of(times: impl ExactSizeIterator<Item = u64>) -> u64 {
+    let count = times.len() as u64;
+    if count == 0 {
+        return 0;
+    }
+    times.sum::<u64>() / count
+}
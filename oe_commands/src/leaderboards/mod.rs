@@ -1,10 +1,12 @@
 use aformat::{ToArrayString, aformat};
-use std::{borrow::Cow, fmt::Write};
+use std::borrow::Cow;
 
-/* mod average;
-mod timed; */
+mod average;
+mod public;
+mod timed;
 
 use crate::{Context, Error};
+use oe_core::leaderboard::{Privacy, progress_lines};
 use poise::{
     CreateReply,
     serenity_prelude::{
@@ -13,13 +15,16 @@ use poise::{
     },
 };
 
+/// Players shown on each page.
+const PAGE_SIZE: usize = 10;
+
 /// Display leaderboards!
 #[allow(clippy::unused_async)]
 #[poise::command(
     slash_command,
     owners_only,
     guild_only,
-    subcommands("progress_slash"),
+    subcommands("progress_slash", "timed::timed", "average::average"),
     subcommand_required
 )]
 pub async fn leaderboard(_: Context<'_>) -> Result<(), Error> {
@@ -39,50 +44,30 @@ pub async fn progress_slash(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 pub async fn progress_inner(ctx: Context<'_>) -> Result<(), Error> {
-    let map = { ctx.data().escape_room.read().user_progress.clone() };
-    let winners_map = { ctx.data().escape_room.read().winners.winners.clone() };
-
-    let mut result = Vec::new();
-    let mut current_string = String::new();
-    let mut count = 0;
-
-    for user in &winners_map {
-        writeln!(current_string, "<@{user}>: completed.").unwrap();
-        count += 1;
-
-        if count == 10 {
-            result.push(current_string);
-            current_string = String::new();
-            count = 0;
-        }
-    }
-
-    let mut progress_vec: Vec<_> = map
-        .iter()
-        .filter(|(key, _)| !winners_map.contains(key)) // Exclude users in winners_map
-        .collect();
-
-    progress_vec.sort_by(|(_, a), (_, b)| b.cmp(a));
-
-    for (key, value) in progress_vec {
-        writeln!(current_string, "<@{key}>: {value}").unwrap();
-        count += 1;
-
-        if count == 10 {
-            result.push(current_string);
-            current_string = String::new();
-            count = 0;
-        }
-    }
+    let lines = progress_lines(&ctx.data(), &Privacy::default());
+    paginate(ctx, "Users sorted by progress", &pages(&lines)).await
+}
 
-    result.push(current_string);
+/// Groups lines into pages of [`PAGE_SIZE`].
+pub(super) fn pages(lines: &[String]) -> Vec<String> {
+    lines
+        .chunks(PAGE_SIZE)
+        .map(|chunk| chunk.join("\n"))
+        .collect()
+}
 
+/// Sends the first page, with buttons to flip through the rest if there are any.
+pub(super) async fn paginate(
+    ctx: Context<'_>,
+    title: &str,
+    result: &[String],
+) -> Result<(), Error> {
     let Some(first) = result.first() else {
         ctx.say("Nobody has answered yet.").await?;
         return Ok(());
     };
 
-    let builder = CreateReply::new().embed(generate_embed(first));
+    let builder = CreateReply::new().embed(generate_embed(title, first));
 
     let is_multipage = result.len() > 1;
     if !is_multipage {
@@ -130,7 +115,7 @@ pub async fn progress_inner(ctx: Context<'_>) -> Result<(), Error> {
                 ctx.http(),
                 CreateInteractionResponse::UpdateMessage(
                     CreateInteractionResponseMessage::default()
-                        .embed(generate_embed(&result[current_page])),
+                        .embed(generate_embed(title, &result[current_page])),
                 ),
             )
             .await;
@@ -138,19 +123,17 @@ pub async fn progress_inner(ctx: Context<'_>) -> Result<(), Error> {
 
     msg.edit(
         ctx,
-        CreateReply::new().embed(generate_embed(&result[current_page])),
+        CreateReply::new().embed(generate_embed(title, &result[current_page])),
     )
     .await?;
     Ok(())
 }
 
-fn generate_embed(page: &str) -> CreateEmbed<'_> {
-    CreateEmbed::new()
-        .title("Users sorted by progress")
-        .description(page)
+fn generate_embed<'a>(title: &'a str, page: &'a str) -> CreateEmbed<'a> {
+    CreateEmbed::new().title(title).description(page)
 }
 
-pub fn commands() -> [crate::Command; 2] {
+pub fn commands() -> [crate::Command; 3] {
     // I want the subcommand to be base commands too.
-    [leaderboard(), progress()]
+    [leaderboard(), progress(), public::public_leaderboard()]
 }
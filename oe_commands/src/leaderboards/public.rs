@@ -0,0 +1,58 @@
+use crate::{Context, Error};
+use oe_core::leaderboard::{Privacy, progress_lines, time_lines};
+use poise::CreateReply;
+
+use super::{pages, paginate};
+
+/// Leaderboards anyone can see.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    rename = "public-leaderboard",
+    slash_command,
+    guild_only,
+    subcommands("progress", "time", "opt_out"),
+    subcommand_required
+)]
+pub async fn public_leaderboard(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// See how far everyone has got!
+#[poise::command(slash_command, guild_only)]
+pub async fn progress(ctx: Context<'_>) -> Result<(), Error> {
+    let privacy = Privacy::public(&ctx.data()).await?;
+    let lines = progress_lines(&ctx.data(), &privacy);
+    paginate(ctx, "Users sorted by progress", &pages(&lines)).await
+}
+
+/// See who finished the fastest!
+#[poise::command(slash_command, guild_only)]
+pub async fn time(ctx: Context<'_>) -> Result<(), Error> {
+    let privacy = Privacy::public(&ctx.data()).await?;
+    let entries = ctx.data().time_leaderboard(None).await?;
+    let lines = time_lines(&entries, false, &privacy);
+    paginate(ctx, "Users sorted by time", &pages(&lines)).await
+}
+
+/// Hide your name on public leaderboards.
+#[poise::command(rename = "opt-out", slash_command, guild_only)]
+pub async fn opt_out(
+    ctx: Context<'_>,
+    #[description = "Show you as anonymous. (defaults to true)"] hidden: Option<bool>,
+) -> Result<(), Error> {
+    let hidden = hidden.unwrap_or(true);
+    ctx.data()
+        .set_leaderboard_opt_out(ctx.author().id, hidden)
+        .await?;
+    ctx.data().mark_leaderboard_dirty();
+
+    let content = if hidden {
+        "You'll be shown as anonymous on public leaderboards."
+    } else {
+        "You'll be shown by name on public leaderboards."
+    };
+    ctx.send(CreateReply::new().content(content).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
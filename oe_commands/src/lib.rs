@@ -7,6 +7,7 @@
 
 use oe_core::structs::{Command, Context, Data, Error, PrefixContext};
 
+mod audit;
 mod badges;
 mod checks;
 mod escape_room;
@@ -20,5 +21,6 @@ pub fn commands() -> Vec<Command> {
         .chain(escape_room::commands())
         .chain(leaderboards::commands())
         .chain(badges::commands())
+        .chain(audit::commands())
         .collect()
 }
@@ -0,0 +1,119 @@
+use poise::serenity_prelude::UserId;
+use sqlx::query;
+
+use crate::structs::{Data, Error};
+
+/// The name used for rooms that haven't been given one.
+pub const DEFAULT_ROOM: &str = "default";
+
+/// A single answer to a question.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct Attempt {
+    pub user_id: UserId,
+    /// The question number, starting from 1.
+    pub question: u16,
+    pub answers: Vec<String>,
+    pub correct: bool,
+    /// How long the attempt put the user on cooldown for.
+    pub cooldown_secs: u64,
+    pub attempted_at: u64,
+}
+
+impl Data {
+    /// The name of the current room, used to tell rooms apart in the database.
+    #[must_use]
+    pub fn room_name(&self) -> String {
+        self.escape_room
+            .read()
+            .name
+            .clone()
+            .unwrap_or_else(|| DEFAULT_ROOM.to_owned())
+    }
+
+    pub async fn record_attempt(&self, attempt: &Attempt) -> Result<(), Error> {
+        let room = self.room_name();
+        let user_id = attempt.user_id.get() as i64;
+        let answers = serde_json::to_string(&attempt.answers)?;
+        let cooldown_secs = attempt.cooldown_secs as i64;
+        let attempted_at = attempt.attempted_at as i64;
+
+        query!(
+            r#"
+            INSERT INTO attempts
+                (user_id, room, question, answers, correct, cooldown_secs, attempted_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            user_id,
+            room,
+            attempt.question,
+            answers,
+            attempt.correct,
+            cooldown_secs,
+            attempted_at
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Every attempt made in the current room, oldest first.
+    pub async fn room_attempts(&self) -> Result<Vec<Attempt>, Error> {
+        let room = self.room_name();
+
+        let attempts = query!(
+            r#"
+            SELECT user_id, question, answers, correct, cooldown_secs, attempted_at
+            FROM attempts
+            WHERE room = ?
+            ORDER BY attempted_at
+            "#,
+            room
+        )
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|row| Attempt {
+            user_id: UserId::new(row.user_id as u64),
+            question: row.question as u16,
+            answers: serde_json::from_str(&row.answers).unwrap_or_default(),
+            correct: row.correct,
+            cooldown_secs: row.cooldown_secs as u64,
+            attempted_at: row.attempted_at as u64,
+        })
+        .collect();
+
+        Ok(attempts)
+    }
+
+    /// A users attempts in the current room, oldest first.
+    pub async fn user_attempts(&self, user_id: UserId) -> Result<Vec<Attempt>, Error> {
+        let room = self.room_name();
+        let id = user_id.get() as i64;
+
+        let attempts = query!(
+            r#"
+            SELECT question, answers, correct, cooldown_secs, attempted_at
+            FROM attempts
+            WHERE room = ? AND user_id = ?
+            ORDER BY attempted_at
+            "#,
+            room,
+            id
+        )
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|row| Attempt {
+            user_id,
+            question: row.question as u16,
+            answers: serde_json::from_str(&row.answers).unwrap_or_default(),
+            correct: row.correct,
+            cooldown_secs: row.cooldown_secs as u64,
+            attempted_at: row.attempted_at as u64,
+        })
+        .collect();
+
+        Ok(attempts)
+    }
+}
@@ -0,0 +1,125 @@
+use poise::serenity_prelude::UserId;
+use sqlx::query;
+
+use crate::now;
+use crate::structs::{Data, Error};
+
+/// A privileged action taken by a member of staff.
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    /// Only set for entries read back from the database.
+    pub id: Option<i64>,
+    pub actor_id: UserId,
+    /// The command that was run.
+    pub action: String,
+    /// The user the action was done to, if any.
+    pub target_id: Option<UserId>,
+    pub arguments: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub created_at: u64,
+}
+
+impl AuditEntry {
+    #[must_use]
+    pub fn new(actor_id: UserId, action: impl Into<String>, arguments: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            actor_id,
+            action: action.into(),
+            target_id: None,
+            arguments: arguments.into(),
+            before: None,
+            after: None,
+            created_at: now(),
+        }
+    }
+
+    #[must_use]
+    pub fn target(mut self, target_id: UserId) -> Self {
+        self.target_id = Some(target_id);
+        self
+    }
+
+    #[must_use]
+    pub fn before(mut self, before: impl Into<String>) -> Self {
+        self.before = Some(before.into());
+        self
+    }
+
+    #[must_use]
+    pub fn after(mut self, after: impl Into<String>) -> Self {
+        self.after = Some(after.into());
+        self
+    }
+}
+
+impl Data {
+    pub async fn record_audit(&self, entry: &AuditEntry) -> Result<(), Error> {
+        let actor_id = entry.actor_id.get() as i64;
+        let target_id = entry.target_id.map(|t| t.get() as i64);
+        let created_at = entry.created_at as i64;
+
+        query!(
+            r#"
+            INSERT INTO audit_log (actor_id, action, target_id, arguments, before, after, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            actor_id,
+            entry.action,
+            target_id,
+            entry.arguments,
+            entry.before,
+            entry.after,
+            created_at
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The most recent entries, newest first.
+    ///
+    /// `user` matches both who did the action and who it was done to, `action` matches any part
+    /// of the command name.
+    pub async fn search_audit(
+        &self,
+        user: Option<UserId>,
+        action: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<AuditEntry>, Error> {
+        let user = user.map(|u| u.get() as i64);
+        let action = action.map(|a| format!("%{a}%"));
+
+        let entries = query!(
+            r#"
+            SELECT id, actor_id, action, target_id, arguments, before, after, created_at
+            FROM audit_log
+            WHERE (?1 IS NULL OR actor_id = ?1 OR target_id = ?1)
+                AND (?2 IS NULL OR action LIKE ?2)
+            ORDER BY created_at DESC, id DESC
+            LIMIT ?3
+            "#,
+            user,
+            action,
+            limit
+        )
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|row| AuditEntry {
+            id: Some(row.id),
+            actor_id: UserId::new(row.actor_id as u64),
+            action: row.action,
+            target_id: row.target_id.map(|t| UserId::new(t as u64)),
+            arguments: row.arguments,
+            before: row.before,
+            after: row.after,
+            created_at: row.created_at as u64,
+        })
+        .collect();
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,341 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use poise::serenity_prelude::{
+    Colour, CreateEmbed, CreateMessage, EditMessage, Http, Timestamp, UserId,
+};
+use sqlx::query;
+
+use crate::messages::is_unknown_message;
+use crate::now;
+use crate::structs::{Data, Error};
+use crate::templates::format_duration;
+
+/// Leaves room for the "and more" line in the embed description.
+const DESCRIPTION_LIMIT: usize = 4000;
+
+/// A players place on the time leaderboard.
+#[derive(Debug, Clone)]
+pub struct TimedEntry {
+    pub user_id: UserId,
+    /// Seconds from starting to finishing, or to now if they haven't finished.
+    pub elapsed: u64,
+    /// Seconds added on for wrong answers.
+    pub penalty: u64,
+    /// Whether the player is one of the winners.
+    pub finished: bool,
+    pub timed_out: bool,
+    /// The question the player is on.
+    pub progress: usize,
+}
+
+impl TimedEntry {
+    /// The time the player is ranked by.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.elapsed + self.penalty
+    }
+}
+
+impl Data {
+    /// How long each player took on each question, keyed by the question number.
+    ///
+    /// Built from the recorded splits, with the first question timed from when the player started.
+    pub async fn solve_times(&self) -> Result<HashMap<(UserId, u16), u64>, Error> {
+        let splits = self.room_splits().await?;
+
+        // when each player first reached each question.
+        let mut reached: HashMap<(UserId, u16), u64> = {
+            let room = self.escape_room.read();
+            room.start_end_time
+                .iter()
+                .map(|(user_id, (start, _))| ((*user_id, 1), *start))
+                .collect()
+        };
+        for split in &splits {
+            reached
+                .entry((split.user_id, split.question))
+                .or_insert(split.reached_at);
+        }
+
+        let times = reached
+            .iter()
+            .filter_map(|(&(user_id, question), &start)| {
+                let end = reached.get(&(user_id, question.checked_add(1)?))?;
+                Some(((user_id, question), end.checked_sub(start)?))
+            })
+            .collect();
+
+        Ok(times)
+    }
+
+    /// Everyone that has started the room, finishers first by their total time, then everyone else
+    /// by how far they got.
+    ///
+    /// `penalty` is the seconds added for each wrong answer, falling back to the rooms setting.
+    pub async fn time_leaderboard(&self, penalty: Option<u64>) -> Result<Vec<TimedEntry>, Error> {
+        let penalty = penalty.or(self.escape_room.read().wrong_answer_penalty);
+
+        let mut wrong_answers: HashMap<UserId, u64> = HashMap::new();
+        if penalty.is_some_and(|p| p > 0) {
+            for attempt in self.room_attempts().await?.iter().filter(|a| !a.correct) {
+                *wrong_answers.entry(attempt.user_id).or_default() += 1;
+            }
+        }
+
+        let splits = self.room_splits().await?;
+        let now = now();
+
+        let mut entries: Vec<TimedEntry> = {
+            let room = self.escape_room.read();
+            // reaching past the last question is the win.
+            let finish_splits: HashMap<UserId, u64> = splits
+                .iter()
+                .filter(|s| usize::from(s.question) > room.questions.len())
+                .map(|s| (s.user_id, s.reached_at))
+                .collect();
+
+            room.start_end_time
+                .iter()
+                .map(|(user_id, (start, end))| {
+                    let finished = room.winners.winners.contains(user_id);
+                    let timed_out = room.timed_out.get(user_id).copied();
+                    let stopped = if finished {
+                        end.or_else(|| finish_splits.get(user_id).copied())
+                    } else {
+                        timed_out
+                    }
+                    .unwrap_or(now);
+
+                    TimedEntry {
+                        user_id: *user_id,
+                        elapsed: stopped.saturating_sub(*start),
+                        penalty: penalty.unwrap_or(0)
+                            * wrong_answers.get(user_id).copied().unwrap_or(0),
+                        finished,
+                        timed_out: timed_out.is_some(),
+                        progress: room.user_progress.get(user_id).copied().unwrap_or(1),
+                    }
+                })
+                .collect()
+        };
+
+        entries.sort_by(|a, b| {
+            b.finished
+                .cmp(&a.finished)
+                .then_with(|| {
+                    if a.finished {
+                        a.total().cmp(&b.total())
+                    } else {
+                        b.progress.cmp(&a.progress)
+                    }
+                })
+                .then_with(|| a.elapsed.cmp(&b.elapsed))
+        });
+
+        Ok(entries)
+    }
+
+    /// Players who asked to be anonymous on public leaderboards.
+    pub async fn leaderboard_opt_outs(&self) -> Result<HashSet<UserId>, Error> {
+        let opt_outs = query!("SELECT user_id FROM leaderboard_opt_outs")
+            .fetch_all(&self.db)
+            .await?
+            .into_iter()
+            .map(|row| UserId::new(row.user_id as u64))
+            .collect();
+
+        Ok(opt_outs)
+    }
+
+    pub async fn set_leaderboard_opt_out(
+        &self,
+        user_id: UserId,
+        opted_out: bool,
+    ) -> Result<(), Error> {
+        let id = user_id.get() as i64;
+
+        if opted_out {
+            let now = now() as i64;
+
+            query!(
+                "INSERT OR IGNORE INTO leaderboard_opt_outs (user_id, opted_out_at) VALUES (?, ?)",
+                id,
+                now
+            )
+            .execute(&self.db)
+            .await?;
+        } else {
+            query!("DELETE FROM leaderboard_opt_outs WHERE user_id = ?", id)
+                .execute(&self.db)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Data {
+    /// Edits the live leaderboard, posting it again if the message is missing.
+    pub async fn update_live_leaderboard(&self, http: &Http) -> Result<(), Error> {
+        let Some(live) = self.escape_room.read().live_leaderboard else {
+            return Ok(());
+        };
+        let embed = self.live_leaderboard_embed().await?;
+
+        if let Some(message_id) = live.message {
+            match live
+                .channel
+                .edit_message(http, message_id, EditMessage::new().embed(embed.clone()))
+                .await
+            {
+                Ok(_) => return Ok(()),
+                // it was deleted, so post it again.
+                Err(e) if is_unknown_message(&e) => {}
+                Err(e) => {
+                    // tried again on the next update.
+                    self.mark_leaderboard_dirty();
+                    return Err(e.into());
+                }
+            }
+        }
+
+        let message = live
+            .channel
+            .send_message(http, CreateMessage::new().embed(embed))
+            .await?;
+
+        let mut room = self.escape_room.write();
+        // it may have been moved or turned off while this was sending.
+        match &mut room.live_leaderboard {
+            Some(current) if current.channel == live.channel => current.message = Some(message.id),
+            _ => return Ok(()),
+        }
+        room.write_questions().unwrap();
+
+        Ok(())
+    }
+
+    async fn live_leaderboard_embed(&self) -> Result<CreateEmbed<'static>, Error> {
+        // it's posted where anyone can see it.
+        let privacy = Privacy::public(self).await?;
+        let lines = progress_lines(self, &privacy);
+
+        let mut description = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            if description.len() + line.len() >= DESCRIPTION_LIMIT {
+                write!(description, "...and {} more", lines.len() - i).unwrap();
+                break;
+            }
+            writeln!(description, "{line}").unwrap();
+        }
+
+        if description.is_empty() {
+            description.push_str("Nobody has started yet.");
+        }
+
+        Ok(CreateEmbed::new()
+            .title("Leaderboard")
+            .description(description)
+            .colour(Colour::BLUE)
+            .timestamp(Timestamp::now()))
+    }
+}
+
+/// What is left out of a leaderboard.
+#[derive(Default)]
+pub struct Privacy {
+    /// Players shown anonymously.
+    pub anonymous: HashSet<UserId>,
+    pub hide_question_numbers: bool,
+}
+
+impl Privacy {
+    /// The rooms settings and everyones opt outs, for leaderboards anyone can see.
+    pub async fn public(data: &Data) -> Result<Self, Error> {
+        Ok(Self {
+            anonymous: data.leaderboard_opt_outs().await?,
+            hide_question_numbers: data.escape_room.read().hide_question_numbers,
+        })
+    }
+
+    #[must_use]
+    pub fn player(&self, user_id: UserId) -> String {
+        if self.anonymous.contains(&user_id) {
+            "Anonymous".to_owned()
+        } else {
+            format!("<@{user_id}>")
+        }
+    }
+}
+
+#[must_use]
+pub fn progress_lines(data: &Data, privacy: &Privacy) -> Vec<String> {
+    let map = { data.escape_room.read().user_progress.clone() };
+    let winners_map = { data.escape_room.read().winners.winners.clone() };
+    let timed_out = { data.escape_room.read().timed_out.clone() };
+
+    let mut lines = Vec::new();
+    for user in &winners_map {
+        lines.push(format!("{}: completed.", privacy.player(*user)));
+    }
+
+    let mut progress_vec: Vec<_> = map
+        .iter()
+        .filter(|(key, _)| !winners_map.contains(key)) // Exclude users in winners_map
+        .collect();
+
+    progress_vec.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    for (key, value) in progress_vec {
+        let player = privacy.player(*key);
+        let timed_out = timed_out.contains_key(key);
+        let line = match (privacy.hide_question_numbers, timed_out) {
+            (false, false) => format!("{player}: {value}"),
+            (false, true) => format!("{player}: {value} (timed out)"),
+            (true, false) => format!("{player}: playing"),
+            (true, true) => format!("{player}: timed out"),
+        };
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[must_use]
+pub fn time_lines(entries: &[TimedEntry], show_unfinished: bool, privacy: &Privacy) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut placement = 0;
+    for entry in entries {
+        let player = privacy.player(entry.user_id);
+        if entry.finished {
+            placement += 1;
+            let penalty = if entry.penalty > 0 {
+                format!(" (+{} penalty)", format_duration(entry.penalty))
+            } else {
+                String::new()
+            };
+
+            lines.push(format!(
+                "`{placement}.` {player}: {}{penalty}",
+                format_duration(entry.total())
+            ));
+        } else if show_unfinished {
+            let status = match (privacy.hide_question_numbers, entry.timed_out) {
+                (false, false) => format!("still playing question {}", entry.progress),
+                (false, true) => format!("timed out on question {}", entry.progress),
+                (true, false) => "still playing".to_owned(),
+                (true, true) => "timed out".to_owned(),
+            };
+
+            lines.push(format!(
+                "{player}: {status}, {}",
+                format_duration(entry.elapsed)
+            ));
+        }
+    }
+
+    lines
+}
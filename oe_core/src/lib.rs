@@ -7,5 +7,22 @@
     clippy::missing_panics_doc
 )]
 
+pub mod attempts;
+pub mod audit;
+pub mod leaderboard;
+pub mod messages;
 mod serialize;
 pub mod structs;
+pub mod templates;
+pub mod timeline;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The current unix timestamp, in seconds.
+#[must_use]
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,160 @@
+use std::borrow::Cow;
+
+use crate::structs::{Error, Question, QuestionKind};
+use poise::serenity_prelude::{
+    self as serenity, ChannelId, Colour, CreateActionRow, CreateAttachment, CreateButton,
+    CreateEmbed, CreateMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    Http, HttpError, MessageId,
+};
+
+/// If the message being edited is gone, so it should be sent again instead of retried.
+#[must_use]
+pub fn is_unknown_message(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if response.status_code.as_u16() == 404
+    )
+}
+
+/// Sends a question to a channel, returning every message that makes it up.
+///
+/// Images are shown in embeds on the question itself, any other files and links are sent in a
+/// message after it.
+pub async fn send_question(
+    http: &Http,
+    channel_id: ChannelId,
+    question: &Question,
+    question_number: u16,
+) -> Result<Vec<MessageId>, Error> {
+    let mut sent = Vec::with_capacity(2);
+    let mut main_embed = CreateEmbed::new()
+        .title(format!("Question #{question_number}"))
+        .description(question.content.clone())
+        .colour(Colour::BLUE);
+
+    let mut embeds = Vec::new();
+    let mut files = Vec::new();
+    let mut extra_files = Vec::new();
+    let mut links = Vec::new();
+
+    let part_media = question.parts.iter().flat_map(|p| {
+        p.media
+            .iter()
+            .map(|m| (Some(p.content.as_str()), m.clone()))
+    });
+    let all_media = question
+        .question_media()
+        .map(|m| (None, m))
+        .chain(part_media);
+
+    let mut main_image = false;
+    for (part, media) in all_media {
+        let embeddable = media.is_image() && !media.spoiler;
+
+        if media.is_url() {
+            if !embeddable {
+                if media.spoiler {
+                    links.push(format!("||{}||", media.source));
+                } else {
+                    links.push(media.source.clone());
+                }
+                continue;
+            }
+        } else {
+            let mut attachment = CreateAttachment::path(&media.source).await.map_err(|_| {
+                format!(
+                    "Could not find {} for question {question_number}",
+                    media.source
+                )
+            })?;
+            attachment.filename = media.file_name().into();
+
+            if !embeddable {
+                extra_files.push(attachment);
+                continue;
+            }
+            files.push(attachment);
+        }
+
+        let image = if media.is_url() {
+            media.source.clone()
+        } else {
+            format!("attachment://{}", media.file_name())
+        };
+
+        // the first image of the question goes on the question itself.
+        if part.is_none() && !main_image {
+            main_embed = main_embed.image(image);
+            main_image = true;
+            continue;
+        }
+
+        let mut embed = CreateEmbed::new().image(image).colour(Colour::BLUE);
+        if let Some(part) = part {
+            embed = embed.title(part.to_owned());
+        }
+        embeds.push(embed);
+    }
+
+    embeds.insert(0, main_embed);
+    let mut builder = CreateMessage::new().embeds(embeds).files(files);
+
+    if let Some(custom_id) = question.custom_id {
+        builder = builder.components(question_components(&question.kind, custom_id.as_str()));
+    }
+
+    sent.push(channel_id.send_message(http, builder).await?.id);
+
+    if !extra_files.is_empty() || !links.is_empty() {
+        let message = channel_id
+            .send_message(
+                http,
+                CreateMessage::new()
+                    .content(links.join("\n"))
+                    .files(extra_files),
+            )
+            .await?;
+        sent.push(message.id);
+    }
+
+    Ok(sent)
+}
+
+fn question_components<'a>(kind: &'a QuestionKind, custom_id: &'a str) -> Vec<CreateActionRow<'a>> {
+    match kind {
+        QuestionKind::Modal | QuestionKind::Review { .. } => {
+            vec![CreateActionRow::Buttons(Cow::Owned(vec![
+                CreateButton::new(custom_id).label("Submit Answer"),
+            ]))]
+        }
+        QuestionKind::Select { options, multiple } => {
+            let options = options
+                .iter()
+                .map(|o| CreateSelectMenuOption::new(o.as_str(), o.as_str()))
+                .collect::<Vec<_>>();
+            let max_values = if *multiple { options.len() as u8 } else { 1 };
+
+            let menu = CreateSelectMenu::new(
+                custom_id,
+                CreateSelectMenuKind::String {
+                    options: Cow::Owned(options),
+                },
+            )
+            .placeholder("Pick your answer")
+            .min_values(1)
+            .max_values(max_values);
+
+            vec![CreateActionRow::SelectMenu(menu)]
+        }
+        // the index is on the end so the buttons can be told apart.
+        QuestionKind::ButtonSequence { buttons, .. } => buttons
+            .iter()
+            .enumerate()
+            .map(|(i, label)| CreateButton::new(format!("{custom_id}#{i}")).label(label.as_str()))
+            .collect::<Vec<_>>()
+            .chunks(5)
+            .map(|row| CreateActionRow::Buttons(Cow::Owned(row.to_vec())))
+            .collect(),
+    }
+}
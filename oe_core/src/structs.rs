@@ -5,10 +5,11 @@ pub type FrameworkContext<'a> = poise::FrameworkContext<'a, Data, Error>;
 pub type Command = poise::Command<Data, Error>;
 
 use crate::serialize::regex_patterns;
+use crate::templates::Messages;
 use aformat::ArrayString;
 use parking_lot::RwLock;
 use poise::ChoiceParameter;
-use poise::serenity_prelude::{ChannelId, GuildId, RoleId, UserId};
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId, RoleId, UserId};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serenity::all::CreateAttachment;
@@ -399,6 +400,50 @@ impl EventBadges {
         Ok(())
     }
 
+    /// Gives a user an events badge without taking away what they already have, contributors
+    /// are upgraded to [`BadgeKind::Both`] and winners stay winners.
+    pub async fn award_badge(
+        &self,
+        user_id: UserId,
+        event_id: u16,
+        winner: bool,
+    ) -> Result<(), Error> {
+        self.populate().await?;
+        let user_id = user_id.get() as i64;
+
+        let existing = query!(
+            r#"
+            SELECT
+                ub.winner AS winner,
+                ub.badge_kind AS badge_kind
+            FROM
+                user_badges ub
+            JOIN
+                users u ON u.id = ub.user_id
+            WHERE
+                u.user_id = ? AND ub.event_id = ?
+            "#,
+            user_id,
+            event_id
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        let (winner, badge_kind) = match existing {
+            Some(row) => {
+                let badge_kind = match BadgeKind::try_from(row.badge_kind)? {
+                    BadgeKind::Participated => BadgeKind::Participated,
+                    BadgeKind::Contributed | BadgeKind::Both => BadgeKind::Both,
+                };
+                (winner || row.winner, badge_kind)
+            }
+            None => (winner, BadgeKind::Participated),
+        };
+
+        self.add_user_badge_(user_id, event_id, i8::from(winner), badge_kind)
+            .await
+    }
+
     pub async fn remove_user_badge(&self, user_id: UserId, name: &str) -> Result<(), Error> {
         self.populate().await?;
 
@@ -596,6 +641,8 @@ impl PartialEq for Event {
 
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct EscapeRoom {
+    /// Tells this room apart from others in the database.
+    pub name: Option<String>,
     pub active: bool,
     pub guild: Option<GuildId>,
     pub winners: Winners,
@@ -604,10 +651,83 @@ pub struct EscapeRoom {
     pub questions: Vec<Question>,
     pub user_progress: HashMap<UserId, usize>,
     pub start_end_time: HashMap<UserId, (u64, Option<u64>)>,
+    /// An optional per-player time budget, counted from their first press on question one.
+    pub time_limit: Option<TimeLimit>,
+    /// Players who ran out of time, alongside when it was noticed.
+    #[serde(default)]
+    pub timed_out: HashMap<UserId, u64>,
+    /// Seconds added to a players time for each wrong answer on the time leaderboard.
+    pub wrong_answer_penalty: Option<u64>,
+    /// Leaves question numbers off public leaderboards, so players can't tell how far others got.
+    #[serde(default)]
+    pub hide_question_numbers: bool,
+    /// A leaderboard message that is kept up to date as players progress.
+    pub live_leaderboard: Option<LiveLeaderboard>,
+    /// Set when the live leaderboard is out of date.
+    #[serde(skip)]
+    pub live_leaderboard_dirty: bool,
+    /// What happens to a players progress when they leave and rejoin.
+    #[serde(default)]
+    pub rejoin_policy: RejoinPolicy,
+    /// Role changes that failed and are waiting to be retried.
+    #[serde(default)]
+    pub role_queue: Vec<PendingRoleChange>,
+    #[serde(default)]
+    pub room_mode: RoomMode,
+    /// Each players private thread, only used with [`RoomMode::Threads`].
+    #[serde(default)]
+    pub player_threads: HashMap<UserId, PlayerThread>,
+    /// Where submissions for [`QuestionKind::Review`] questions are sent.
+    pub review_channel: Option<ChannelId>,
+    /// Submissions waiting on a decision from staff.
+    #[serde(default)]
+    pub reviews: Vec<PendingReview>,
+    /// Where staff actions are mirrored to, they are always kept in the database.
+    pub audit_channel: Option<ChannelId>,
+    /// How many seconds players get to fill in each answer modal, defaults to 60.
+    pub modal_timeout: Option<u64>,
+    /// Templates for the messages players see.
+    #[serde(default)]
+    pub messages: Messages,
+    /// The event badge handed out to players, nothing is awarded if unset.
+    pub badge: Option<RoomBadge>,
+    /// Who gets told about problems with the room, and where.
+    #[serde(default)]
+    pub notifications: Notifications,
+    /// Also append every attempt to `answers_log.jsonl`.
+    #[serde(default)]
+    pub mirror_answers_log: bool,
     // if errors happened when trying to go into the next question.
     // contains a bool to say if its hard failed and no longer retrying.
     #[serde(skip)]
     pub cooldowns: CooldownHandler,
+    /// Buttons pressed so far by players answering a [`QuestionKind::ButtonSequence`], keyed by
+    /// the player and question index.
+    #[serde(skip)]
+    pub button_sequences: HashMap<(UserId, u16), Vec<String>>,
+    /// When each alert was last sent, used to skip duplicates.
+    #[serde(skip)]
+    pub sent_alerts: HashMap<(AlertKind, String), u64>,
+}
+
+/// Links the escape room to an [`Event`] so badges are awarded automatically.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RoomBadge {
+    pub event_id: u16,
+    /// Placements that are marked as winners on the badge, starting from 1, empty marks every
+    /// winner. Ignored when [`Winners::tiers`] are set.
+    #[serde(default)]
+    pub winner_placements: Vec<usize>,
+    /// Players get a participated badge once they are moved onto this question number, 1 awards
+    /// it on their first press.
+    pub participation_question: Option<usize>,
+}
+
+impl RoomBadge {
+    #[must_use]
+    pub fn is_winner(&self, placement: usize) -> bool {
+        self.winner_placements.is_empty() || self.winner_placements.contains(&placement)
+    }
 }
 
 #[derive(Serialize, Deserialize, Default, Debug)]
@@ -618,6 +738,256 @@ pub struct Winners {
     pub winner_channel: Option<ChannelId>,
     pub first_winner_role: Option<RoleId>,
     pub winner_role: Option<RoleId>,
+    /// Replaces the first winner and winner roles when set, checked in order.
+    #[serde(default)]
+    pub tiers: Vec<PlacementTier>,
+    /// The standings embed in the winner channel, edited as people finish.
+    pub standings_message: Option<MessageId>,
+}
+
+impl Winners {
+    /// Where the user finished, starting from 1.
+    #[must_use]
+    pub fn placement(&self, user_id: UserId) -> Option<usize> {
+        self.winners
+            .iter()
+            .position(|u| *u == user_id)
+            .map(|p| p + 1)
+    }
+
+    /// The tier a placement falls into, if any tiers are set.
+    #[must_use]
+    pub fn tier(&self, placement: usize) -> Option<&PlacementTier> {
+        self.tiers
+            .iter()
+            .find(|t| t.up_to.is_none_or(|up_to| placement <= up_to))
+    }
+
+    /// The role given for a placement.
+    #[must_use]
+    pub fn role_for(&self, placement: usize) -> Option<RoleId> {
+        if !self.tiers.is_empty() {
+            return self.tier(placement).and_then(|t| t.role);
+        }
+
+        if placement == 1 {
+            self.first_winner_role
+        } else {
+            self.winner_role
+        }
+    }
+
+    /// Every role that can be given to a winner.
+    #[must_use]
+    pub fn roles(&self) -> Vec<RoleId> {
+        let mut roles: Vec<RoleId> = self
+            .first_winner_role
+            .into_iter()
+            .chain(self.winner_role)
+            .chain(self.tiers.iter().filter_map(|t| t.role))
+            .collect();
+        roles.sort_unstable();
+        roles.dedup();
+        roles
+    }
+
+    /// If every placement has a role to be given.
+    #[must_use]
+    pub fn roles_configured(&self) -> bool {
+        if self.tiers.is_empty() {
+            return self.first_winner_role.is_some() && self.winner_role.is_some();
+        }
+
+        self.tiers.iter().all(|t| t.role.is_some())
+            && self.tiers.last().is_some_and(|t| t.up_to.is_none())
+    }
+}
+
+/// A range of placements that share a role, announcement and badge.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlacementTier {
+    /// Shown as the heading in the standings.
+    pub name: String,
+    /// The last placement in this tier, starting from 1, `None` covers everyone left.
+    pub up_to: Option<usize>,
+    pub role: Option<RoleId>,
+    /// Posted in the winner channel instead of the rooms winner message, supports the same
+    /// placeholders as [`Messages`].
+    pub announcement: Option<String>,
+    /// If players in this tier are marked as winners on the rooms badge.
+    #[serde(default)]
+    pub badge_winner: bool,
+}
+
+/// Staff alert routing, anything without a route goes to the error channel and mentions staff.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Notifications {
+    #[serde(default)]
+    pub staff_users: Vec<UserId>,
+    #[serde(default)]
+    pub staff_roles: Vec<RoleId>,
+    #[serde(default)]
+    pub routes: HashMap<AlertKind, AlertRoute>,
+    /// Alerts are still posted during quiet hours, just without mentions.
+    pub quiet_hours: Option<QuietHours>,
+    /// How many seconds the same alert is skipped for after being sent, defaults to 300.
+    pub dedup_seconds: Option<u64>,
+}
+
+impl Notifications {
+    /// Where an alert goes and who it mentions.
+    #[must_use]
+    pub fn route(&self, kind: AlertKind, error_channel: Option<ChannelId>) -> AlertRoute {
+        let route = self.routes.get(&kind);
+        AlertRoute {
+            channel: route.and_then(|r| r.channel).or(error_channel),
+            users: route
+                .and_then(|r| r.users.clone())
+                .or_else(|| Some(self.staff_users.clone())),
+            roles: route
+                .and_then(|r| r.roles.clone())
+                .or_else(|| Some(self.staff_roles.clone())),
+            dedup_seconds: route.and_then(|r| r.dedup_seconds).or(self.dedup_seconds),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// Roles couldn't be changed after every retry.
+    RoleFailure,
+    /// A player answered a question behind where they should be.
+    WrongQuestion,
+    /// A player finished without a start time being recorded.
+    MissingStartTime,
+    /// A player answered a question ahead of where they should be.
+    SuspectedCheating,
+}
+
+/// Overrides for a single [`AlertKind`], anything unset uses the defaults in [`Notifications`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct AlertRoute {
+    pub channel: Option<ChannelId>,
+    /// An empty list mentions nobody.
+    pub users: Option<Vec<UserId>>,
+    pub roles: Option<Vec<RoleId>>,
+    pub dedup_seconds: Option<u64>,
+}
+
+/// A range of UTC hours, wrapping past midnight if `end` is before `start`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct QuietHours {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl QuietHours {
+    #[must_use]
+    pub fn contains(self, hour: u8) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct LiveLeaderboard {
+    pub channel: ChannelId,
+    /// Posted again if this is missing or was deleted.
+    pub message: Option<MessageId>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct TimeLimit {
+    /// How long each player has, in seconds.
+    pub seconds: u64,
+    /// Role given once a player runs out of time, used to let them into the time's up channel.
+    pub role: Option<RoleId>,
+    /// Where players are pointed to once their time is up.
+    pub channel: Option<ChannelId>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejoinPolicy {
+    /// Progress is wiped and any question roles they still have are removed.
+    #[default]
+    Reset,
+    /// Progress is kept and the role for their current question is given back.
+    Restore,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomMode {
+    /// Every question has its own channel, locked behind a role.
+    #[default]
+    Channels,
+    /// The first question has a channel, everything after is sent to a private thread made for
+    /// each player, no question roles are needed.
+    Threads,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlayerThread {
+    pub channel: ChannelId,
+    /// The messages making up the question the player is currently on.
+    pub messages: Vec<MessageId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingReview {
+    pub user_id: UserId,
+    /// The index of the question being answered.
+    pub question: usize,
+    pub channel: ChannelId,
+    pub message: MessageId,
+    /// Unix timestamp of when it was submitted.
+    pub submitted_at: u64,
+    /// What was submitted, the file name for screenshots.
+    #[serde(default)]
+    pub answers: Vec<String>,
+}
+
+/// A role change that failed, waiting to be retried.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingRoleChange {
+    /// Tells apart queued changes for the same user.
+    #[serde(default)]
+    pub id: u64,
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    /// Set to None once it has gone through.
+    pub add: Option<RoleId>,
+    /// Set to None once it has gone through.
+    pub remove: Option<RoleId>,
+    /// If the user should be moved onto the next question once the change succeeds.
+    pub advance: bool,
+    pub attempts: u8,
+    /// Unix timestamp of the next attempt.
+    pub retry_at: u64,
+}
+
+impl PendingRoleChange {
+    #[must_use]
+    pub fn new(
+        guild_id: GuildId,
+        user_id: UserId,
+        add: Option<RoleId>,
+        remove: Option<RoleId>,
+        advance: bool,
+    ) -> Self {
+        PendingRoleChange {
+            id: 0,
+            guild_id,
+            user_id,
+            add,
+            remove,
+            advance,
+            attempts: 0,
+            retry_at: 0,
+        }
+    }
 }
 
 /// Holds the last invocation time of an interaction for a user.
@@ -635,11 +1005,19 @@ pub struct Question {
     pub content: String,
     pub image_path: Option<String>,
     pub attachment_path: Option<String>,
+    /// Images, files and links shown alongside the question.
+    #[serde(default)]
+    pub media: Vec<Media>,
+    /// Revealed to the player once they solve the question, supports the same placeholders as
+    /// [`Messages`].
+    pub success_text: Option<String>,
     pub parts: Vec<QuestionPart>,
     pub channel: Option<ChannelId>,
     pub custom_id: Option<ArrayString<26>>,
     /// Is None when not set up or if first question.
     pub role_id: Option<RoleId>,
+    #[serde(default)]
+    pub kind: QuestionKind,
 }
 /// A part of a question containing its own answers and content.
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
@@ -648,6 +1026,91 @@ pub struct QuestionPart {
     pub answers: Vec<String>,
     #[serde(with = "regex_patterns")]
     pub regex_answers: Vec<Regex>,
+    /// Use a long text field instead of a single line.
+    #[serde(default)]
+    pub paragraph: bool,
+    /// Shown in its own embed under the question, as modals can't show images.
+    #[serde(default)]
+    pub media: Vec<Media>,
+}
+
+/// An image, file or link attached to a question.
+///
+/// Images are shown in embeds, everything else is sent in a message after the question.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Media {
+    /// A path to a local file or a http(s) url.
+    pub source: String,
+    /// Hides the media behind a spoiler, spoilered images are sent as files instead of embeds.
+    #[serde(default)]
+    pub spoiler: bool,
+}
+
+impl Media {
+    #[must_use]
+    pub fn new(source: String) -> Self {
+        Media {
+            source,
+            spoiler: false,
+        }
+    }
+
+    #[must_use]
+    pub fn is_url(&self) -> bool {
+        self.source.starts_with("https://") || self.source.starts_with("http://")
+    }
+
+    /// If this can be shown as an embed image.
+    #[must_use]
+    pub fn is_image(&self) -> bool {
+        let path = self.source.split(['?', '#']).next().unwrap_or_default();
+        std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| {
+                ["png", "jpg", "jpeg", "gif", "webp"].contains(&e.to_ascii_lowercase().as_str())
+            })
+    }
+
+    /// The name the file will be uploaded with.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        let name = std::path::Path::new(&self.source)
+            .file_name()
+            .map_or_else(|| "file".into(), |n| n.to_string_lossy().into_owned());
+
+        if self.spoiler {
+            format!("SPOILER_{name}")
+        } else {
+            name
+        }
+    }
+}
+
+/// How a question is answered.
+///
+/// Anything but [`QuestionKind::Modal`] is submitted as a single answer against the first part,
+/// with each picked option or pressed button separated by `", "`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub enum QuestionKind {
+    /// A modal with a text field for each part.
+    #[default]
+    Modal,
+    /// A select menu, picked options are in the order they are listed here.
+    Select {
+        options: Vec<String>,
+        #[serde(default)]
+        multiple: bool,
+    },
+    /// Buttons that need to be pressed in the right order, the answer is checked once `length`
+    /// buttons have been pressed.
+    ButtonSequence { buttons: Vec<String>, length: usize },
+    /// Judged by staff in the review channel, players either send a screenshot to the bot in
+    /// direct messages or fill in a text field for each part.
+    Review {
+        #[serde(default)]
+        image: bool,
+    },
 }
 
 impl Question {
@@ -657,11 +1120,82 @@ impl Question {
             content,
             image_path: None,
             attachment_path: None,
+            media: Vec::new(),
+            success_text: None,
             parts,
             channel: None,
             custom_id: None,
             role_id: None,
+            kind: QuestionKind::Modal,
+        }
+    }
+
+    /// If the question has everything it needs to be answered.
+    #[must_use]
+    pub fn is_answerable(&self) -> bool {
+        let has_answers =
+            !self.parts.is_empty() && self.parts.iter().all(|p| !p.answers.is_empty());
+
+        match &self.kind {
+            QuestionKind::Modal => has_answers,
+            // discord only allows 25 options and 25 buttons per message.
+            QuestionKind::Select { options, .. } => {
+                has_answers && !options.is_empty() && options.len() <= 25
+            }
+            QuestionKind::ButtonSequence { buttons, length } => {
+                has_answers && !buttons.is_empty() && buttons.len() <= 25 && *length != 0
+            }
+            // staff decide what the right answer is.
+            QuestionKind::Review { image } => *image || !self.parts.is_empty(),
+        }
+    }
+
+    /// The questions own media, including the older `image_path` and `attachment_path`.
+    pub fn question_media(&self) -> impl Iterator<Item = Media> + '_ {
+        self.image_path
+            .iter()
+            .chain(&self.attachment_path)
+            .map(|p| Media::new(p.clone()))
+            .chain(self.media.iter().cloned())
+    }
+
+    /// Anything that would stop the question from being sent.
+    #[must_use]
+    pub fn media_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let all_media: Vec<Media> = self
+            .question_media()
+            .chain(self.parts.iter().flat_map(|p| p.media.iter().cloned()))
+            .collect();
+
+        for media in &all_media {
+            if !media.is_url() && !std::path::Path::new(&media.source).is_file() {
+                problems.push(format!("`{}` does not exist", media.source));
+            }
+        }
+
+        // discord allows 10 of each per message, the question itself takes an embed.
+        let embeds = all_media
+            .iter()
+            .filter(|m| m.is_image() && !m.spoiler)
+            .count();
+        if embeds > 9 {
+            problems.push(format!(
+                "{embeds} images is more than the 9 that can be shown"
+            ));
         }
+
+        let files = all_media
+            .iter()
+            .filter(|m| !m.is_url() && (!m.is_image() || m.spoiler))
+            .count();
+        if files > 10 {
+            problems.push(format!(
+                "{files} files is more than the 10 that can be sent"
+            ));
+        }
+
+        problems
     }
 }
 
@@ -676,15 +1210,99 @@ impl EscapeRoom {
 }
 
 impl Data {
-    pub fn user_next_question(&self, user_id: UserId) -> usize {
-        let mut room = self.escape_room.write();
-        let progress = room.user_progress.entry(user_id).or_insert(1);
-        *progress += 1;
-        let new = *progress;
-        room.write_questions().unwrap();
+    /// Moves the user onto the next question, recording when they got there.
+    pub async fn user_next_question(&self, user_id: UserId) -> usize {
+        let new = {
+            let mut room = self.escape_room.write();
+            let progress = room.user_progress.entry(user_id).or_insert(1);
+            *progress += 1;
+            let new = *progress;
+            room.live_leaderboard_dirty = true;
+            room.write_questions().unwrap();
+            new
+        };
+
+        if let Err(e) = self.record_split(user_id, new).await {
+            tracing::error!(%user_id, question = new, "Couldn't record the split: {e}");
+        }
         new
     }
 
+    /// Records a timeout if the user has gone over the time limit at `now`.
+    ///
+    /// Returns the time limit if the user has run out of time, and if this was the first time it
+    /// was noticed.
+    pub fn check_time_limit(&self, user_id: UserId, now: u64) -> Option<(TimeLimit, bool)> {
+        let limit = {
+            let mut room = self.escape_room.write();
+            let limit = room.time_limit.clone()?;
+
+            if room.timed_out.contains_key(&user_id) {
+                return Some((limit, false));
+            }
+
+            if room.winners.winners.contains(&user_id) {
+                return None;
+            }
+
+            let (start, _) = room.start_end_time.get(&user_id).copied()?;
+
+            if now.saturating_sub(start) <= limit.seconds {
+                return None;
+            }
+
+            room.timed_out.insert(user_id, now);
+            room.write_questions().unwrap();
+            limit
+        };
+
+        self.mark_leaderboard_dirty();
+        Some((limit, true))
+    }
+
+    /// How many seconds the user has spent in the escape room, stopping when they finish.
+    #[must_use]
+    pub fn elapsed(&self, user_id: UserId, now: u64) -> Option<u64> {
+        let (start, end) = self
+            .escape_room
+            .read()
+            .start_end_time
+            .get(&user_id)
+            .copied()?;
+        Some(end.unwrap_or(now).saturating_sub(start))
+    }
+
+    /// Gets the role a user should have for where they are in the escape room.
+    pub fn expected_role(&self, user_id: UserId) -> Option<RoleId> {
+        let room = self.escape_room.read();
+
+        if let Some(placement) = room.winners.placement(user_id) {
+            return room.winners.role_for(placement);
+        }
+
+        let progress = *room.user_progress.get(&user_id)?;
+        room.questions.get(progress.checked_sub(1)?)?.role_id
+    }
+
+    /// If the user has a role change waiting to be retried.
+    pub fn has_pending_role_change(&self, user_id: UserId) -> bool {
+        self.escape_room
+            .read()
+            .role_queue
+            .iter()
+            .any(|c| c.user_id == user_id)
+    }
+
+    /// Gets the channel the user should be answering the question at `index` in.
+    pub fn question_channel(&self, user_id: UserId, index: usize) -> Option<ChannelId> {
+        let room = self.escape_room.read();
+        if room.room_mode == RoomMode::Threads && index != 0 {
+            return room.player_threads.get(&user_id).map(|t| t.channel);
+        }
+
+        room.questions.get(index)?.channel
+    }
+
     pub fn get_user_question(&self, user_id: UserId) -> usize {
         let room = self.escape_room.read();
         *room.user_progress.get(&user_id).unwrap_or(&1)
@@ -709,6 +1327,11 @@ impl Data {
         self.escape_room.read().active
     }
 
+    /// Has the live leaderboard updated next time it is checked.
+    pub fn mark_leaderboard_dirty(&self) {
+        self.escape_room.write().live_leaderboard_dirty = true;
+    }
+
     /// Set the current status of the escape room.
     ///
     /// Returns the old value.
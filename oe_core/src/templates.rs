@@ -0,0 +1,123 @@
+use poise::serenity_prelude::{ChannelId, UserId};
+use serde::{Deserialize, Serialize};
+
+/// Player facing messages for the room, anything left unset uses the default.
+///
+/// Templates can use `{user}`, `{question}`, `{channel}`, `{elapsed}` and `{placement}`, any
+/// that don't apply to a message are left empty.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Messages {
+    /// Sent to the player after a correct answer.
+    pub correct: Option<String>,
+    /// Sent to the player after a wrong answer.
+    pub wrong_answer: Option<String>,
+    /// Sent to the player when they finish the last question.
+    pub won: Option<String>,
+    /// Posted in the winner channel for the first winner.
+    pub first_winner: Option<String>,
+    /// Posted in the winner channel for everyone else.
+    pub winner: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum MessageKind {
+    Correct,
+    WrongAnswer,
+    Won,
+    FirstWinner,
+    Winner,
+}
+
+impl MessageKind {
+    #[must_use]
+    pub fn default_template(self) -> &'static str {
+        match self {
+            MessageKind::Correct => "That was the correct answer, please proceed to {channel}!",
+            MessageKind::WrongAnswer => "That was not the right answer!",
+            MessageKind::Won => "You won the escape room! {channel}!",
+            MessageKind::FirstWinner => {
+                "{user} was the first to win the escape room! Congratulations!"
+            }
+            MessageKind::Winner => "Congratulations! {user}",
+        }
+    }
+}
+
+/// The values filled into a template.
+#[derive(Default)]
+pub struct Placeholders {
+    pub user: Option<UserId>,
+    /// The question number, starting from 1.
+    pub question: Option<usize>,
+    pub channel: Option<ChannelId>,
+    /// Seconds since the player started the room.
+    pub elapsed: Option<u64>,
+    /// Where the player finished, starting from 1.
+    pub placement: Option<usize>,
+}
+
+impl Messages {
+    /// Renders the rooms template for `kind`, falling back to the default.
+    #[must_use]
+    pub fn render(&self, kind: MessageKind, values: &Placeholders) -> String {
+        let template = match kind {
+            MessageKind::Correct => &self.correct,
+            MessageKind::WrongAnswer => &self.wrong_answer,
+            MessageKind::Won => &self.won,
+            MessageKind::FirstWinner => &self.first_winner,
+            MessageKind::Winner => &self.winner,
+        };
+
+        render(
+            template.as_deref().unwrap_or(kind.default_template()),
+            values,
+        )
+    }
+}
+
+/// Fills in the placeholders of any template.
+#[must_use]
+pub fn render(template: &str, values: &Placeholders) -> String {
+    let user = values.user.map(|u| format!("<@{u}>")).unwrap_or_default();
+    let question = values.question.map(|q| q.to_string()).unwrap_or_default();
+    let channel = values
+        .channel
+        .map(|c| format!("<#{c}>"))
+        .unwrap_or_default();
+    let elapsed = values.elapsed.map(format_duration).unwrap_or_default();
+    let placement = values.placement.map(ordinal).unwrap_or_default();
+
+    template
+        .replace("{user}", &user)
+        .replace("{question}", &question)
+        .replace("{channel}", &channel)
+        .replace("{elapsed}", &elapsed)
+        .replace("{placement}", &placement)
+}
+
+#[must_use]
+pub fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let seconds = seconds % 60;
+
+    if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{n}{suffix}")
+}
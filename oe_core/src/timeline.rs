@@ -0,0 +1,159 @@
+use poise::serenity_prelude::{RoleId, UserId};
+use sqlx::query;
+
+use crate::now;
+use crate::structs::{Data, Error};
+
+/// When a user moved onto a question.
+#[derive(Debug, Clone)]
+pub struct Split {
+    pub user_id: UserId,
+    pub question: u16,
+    pub reached_at: u64,
+}
+
+/// An attempt at changing a users roles.
+#[derive(Debug, Clone)]
+pub struct RoleChange {
+    pub user_id: UserId,
+    pub added: Option<RoleId>,
+    pub removed: Option<RoleId>,
+    pub succeeded: bool,
+    pub changed_at: u64,
+}
+
+impl Data {
+    pub async fn record_split(&self, user_id: UserId, question: usize) -> Result<(), Error> {
+        let room = self.room_name();
+        let user_id = user_id.get() as i64;
+        let question = question as i64;
+        let reached_at = now() as i64;
+
+        query!(
+            "INSERT INTO splits (user_id, room, question, reached_at) VALUES (?, ?, ?, ?)",
+            user_id,
+            room,
+            question,
+            reached_at
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_role_change(
+        &self,
+        user_id: UserId,
+        added: Option<RoleId>,
+        removed: Option<RoleId>,
+        succeeded: bool,
+    ) -> Result<(), Error> {
+        let room = self.room_name();
+        let user_id = user_id.get() as i64;
+        let added = added.map(|r| r.get() as i64);
+        let removed = removed.map(|r| r.get() as i64);
+        let changed_at = now() as i64;
+
+        query!(
+            r#"
+            INSERT INTO role_changes (user_id, room, added, removed, succeeded, changed_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            user_id,
+            room,
+            added,
+            removed,
+            succeeded,
+            changed_at
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Every split in the current room, oldest first.
+    pub async fn room_splits(&self) -> Result<Vec<Split>, Error> {
+        let room = self.room_name();
+
+        let splits = query!(
+            r#"
+            SELECT user_id, question, reached_at
+            FROM splits
+            WHERE room = ?
+            ORDER BY reached_at
+            "#,
+            room
+        )
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|row| Split {
+            user_id: UserId::new(row.user_id as u64),
+            question: row.question as u16,
+            reached_at: row.reached_at as u64,
+        })
+        .collect();
+
+        Ok(splits)
+    }
+
+    /// A users splits in the current room, oldest first.
+    pub async fn user_splits(&self, user_id: UserId) -> Result<Vec<Split>, Error> {
+        let room = self.room_name();
+        let id = user_id.get() as i64;
+
+        let splits = query!(
+            r#"
+            SELECT question, reached_at
+            FROM splits
+            WHERE room = ? AND user_id = ?
+            ORDER BY reached_at
+            "#,
+            room,
+            id
+        )
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|row| Split {
+            user_id,
+            question: row.question as u16,
+            reached_at: row.reached_at as u64,
+        })
+        .collect();
+
+        Ok(splits)
+    }
+
+    /// A users role changes in the current room, oldest first.
+    pub async fn user_role_changes(&self, user_id: UserId) -> Result<Vec<RoleChange>, Error> {
+        let room = self.room_name();
+        let id = user_id.get() as i64;
+
+        let changes = query!(
+            r#"
+            SELECT added, removed, succeeded, changed_at
+            FROM role_changes
+            WHERE room = ? AND user_id = ?
+            ORDER BY changed_at
+            "#,
+            room,
+            id
+        )
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|row| RoleChange {
+            user_id,
+            added: row.added.map(|r| RoleId::new(r as u64)),
+            removed: row.removed.map(|r| RoleId::new(r as u64)),
+            succeeded: row.succeeded,
+            changed_at: row.changed_at as u64,
+        })
+        .collect();
+
+        Ok(changes)
+    }
+}
@@ -0,0 +1,72 @@
+use oe_core::structs::Data;
+use poise::serenity_prelude::{CreateAllowedMentions, CreateMessage, Http, UserId};
+
+use crate::FrameworkContext;
+
+/// Gives the player a participated badge if they just reached the rooms participation question.
+///
+/// Only call this once their progress has been recorded.
+pub(super) async fn reached_question(
+    http: &Http,
+    data: &Data,
+    user_id: UserId,
+    question_number: usize,
+) {
+    let badge = data.escape_room.read().badge.clone();
+    let Some(badge) = badge else {
+        return;
+    };
+
+    if badge.participation_question == Some(question_number) {
+        award(http, data, user_id, badge.event_id, false).await;
+    }
+}
+
+/// Gives a winner the rooms badge, marked as a winner if their placement counts.
+pub(super) async fn won(framework: FrameworkContext<'_>, user_id: UserId, placement: usize) {
+    let (event_id, winner) = {
+        let data = framework.user_data();
+        let room = data.escape_room.read();
+        let Some(badge) = &room.badge else {
+            return;
+        };
+
+        let winner = if room.winners.tiers.is_empty() {
+            badge.is_winner(placement)
+        } else {
+            room.winners.tier(placement).is_some_and(|t| t.badge_winner)
+        };
+        (badge.event_id, winner)
+    };
+
+    award(
+        &framework.serenity_context.http,
+        &framework.user_data(),
+        user_id,
+        event_id,
+        winner,
+    )
+    .await;
+}
+
+async fn award(http: &Http, data: &Data, user_id: UserId, event_id: u16, winner: bool) {
+    let Err(e) = data.badges.award_badge(user_id, event_id, winner).await else {
+        return;
+    };
+
+    tracing::error!(%user_id, "Couldn't award the escape room badge: {e}");
+    let error_channel = data.escape_room.read().error_channel;
+    if let Some(error_channel) = error_channel {
+        let _ = error_channel
+            .send_message(
+                http,
+                CreateMessage::new()
+                    .content(format!(
+                        "I couldn't give <@{user_id}> the escape room badge, please add it with \
+                         `add-user-badge`: {e}"
+                    ))
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await;
+    }
+}
@@ -7,7 +7,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-const WRONG_ANSWER_COOLDOWN: Duration = Duration::from_secs(150);
+pub const WRONG_ANSWER_COOLDOWN: Duration = Duration::from_secs(150);
 const WRONG_CHANNEL_MESSAGE_COOLDOWN: Duration = Duration::from_secs(1800);
 
 /// Checks the cooldown, returns the Duration left if a cooldown is active.
@@ -26,7 +26,7 @@ pub fn check_cooldown(data: &Arc<Data>, user_id: UserId, question_number: u16) -
 }
 
 pub fn wrong_answer_cooldown_handler(data: &Arc<Data>, user_id: UserId, question_number: u16) {
-    println!("{user_id}: answered incorrectly.");
+    tracing::info!(%user_id, question = question_number + 1, "Answered incorrectly.");
     let mut room = data.escape_room.write();
     room.cooldowns
         .wrong_answer
@@ -35,7 +35,7 @@ pub fn wrong_answer_cooldown_handler(data: &Arc<Data>, user_id: UserId, question
 
 /// Returns true if the message has been announced and is on cooldown.
 pub fn check_wrong_question_cooldown(data: &Arc<Data>, user_id: UserId) -> bool {
-    println!("Checking wrong question cooldown for {user_id}.");
+    tracing::debug!(%user_id, "Checking wrong question cooldown.");
     let room = data.escape_room.read();
     let Some(user_cooldown) = room.cooldowns.wrong_question.get(&user_id).copied() else {
         return false;
@@ -48,7 +48,7 @@ pub fn check_wrong_question_cooldown(data: &Arc<Data>, user_id: UserId) -> bool
 }
 
 pub fn wrong_question_cooldown_handler(data: &Arc<Data>, user_id: UserId) {
-    println!("{user_id}: answered the wrong question.");
+    tracing::info!(%user_id, "Answered the wrong question.");
     let mut room = data.escape_room.write();
     room.cooldowns
         .wrong_question
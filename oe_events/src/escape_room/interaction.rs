@@ -1,23 +1,33 @@
-use std::{collections::hash_map::Entry, sync::Arc};
+use std::{borrow::Cow, sync::Arc, time::Duration};
 
 use crate::{Error, FrameworkContext};
 
 use super::cooldown::{
-    check_cooldown, check_wrong_question_cooldown, wrong_answer_cooldown_handler,
-    wrong_question_cooldown_handler,
+    WRONG_ANSWER_COOLDOWN, check_cooldown, check_wrong_question_cooldown,
+    wrong_answer_cooldown_handler, wrong_question_cooldown_handler,
 };
-use crate::escape_room::move_channel::move_to_next_channel;
-use oe_core::structs::{Data, Question};
+use super::notify::{self, Alert};
+use super::time_limit::check_time_limit;
+use super::{badges, restore_roles, review};
+use crate::escape_room::move_channel::{Player, move_to_next_channel};
+use oe_core::attempts::Attempt;
+use oe_core::now;
+use oe_core::structs::{AlertKind, Data, Question, QuestionKind, RejoinPolicy, RoomMode};
+use oe_core::templates::{MessageKind, Placeholders};
 use poise::serenity_prelude::{
-    self as serenity, ChannelId, ComponentInteraction, CreateInteractionResponse,
-    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateMessage,
-    CreateQuickModal,
+    self as serenity, ChannelId, ComponentInteraction, ComponentInteractionCollector,
+    ComponentInteractionDataKind, CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateQuickModal, UserId,
 };
 
 use ::serenity::all::QuickModal;
 use aformat::aformat;
 use small_fixed_array::{FixedArray, FixedString};
 
+/// The most inputs Discord allows in a single modal.
+const MODAL_INPUTS: usize = 5;
+const DEFAULT_MODAL_TIMEOUT: u64 = 60;
+
 // Discord ids will never be small enough for this.
 #[allow(clippy::cast_sign_loss)]
 // oh my god this is pain.
@@ -27,47 +37,45 @@ pub(crate) async fn handle_component(
     press: &ComponentInteraction,
 ) -> Result<(), Error> {
     let data = framework.user_data();
-    let Ok((question, log_channel, right_question, index, question_count)) = checks(&data, press)
-    else {
+    let Ok((question, log_channel, right_question, index)) = checks(&data, press) else {
         return Ok(());
     };
+    tracing::Span::current().record("question", index + 1);
+
+    if data.has_pending_role_change(press.user.id) {
+        press
+            .create_response(
+                &framework.serenity_context.http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content("Your roles are still being updated, please wait a moment!"),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    if check_time_limit(framework, press).await? {
+        return Ok(());
+    }
 
-    let mut send_dumb_error = false;
     // doesn't respond.
     if index == 0 {
         // why try_insert unstable?
-        {
-            data.escape_room
-                .write()
-                .start_end_time
+        let started = {
+            let mut room = data.escape_room.write();
+            let started = !room.start_end_time.contains_key(&press.user.id);
+            room.start_end_time
                 .entry(press.user.id)
                 .or_insert((press.id.created_at().unix_timestamp() as u64, None));
-        }
-        data.write_questions().unwrap();
-    } else if index + 1 == question_count {
-        match data.escape_room.write().start_end_time.entry(press.user.id) {
-            Entry::Occupied(mut e) => {
-                let (_, end) = e.get_mut();
-                *end = Some(press.id.created_at().unix_timestamp() as u64);
-            }
-            Entry::Vacant(_) => send_dumb_error = true,
-        }
+            started
+        };
         data.write_questions().unwrap();
-    }
 
-    if send_dumb_error {
-        let error_channel = { data.escape_room.read().error_channel };
-        if let Some(error_channel) = error_channel {
-            let _ = error_channel
-                .send_message(
-                    &framework.serenity_context.http,
-                    CreateMessage::new().content(format!(
-                        "<@{}> attempted to finish escape room at <t:{}> without starting \
-                         timestamp?",
-                        press.user.id,
-                        press.id.created_at().unix_timestamp()
-                    )),
-                )
+        // nobody is moved onto the first question, so starting it counts as reaching it.
+        if started {
+            badges::reached_question(&framework.serenity_context.http, &data, press.user.id, 1)
                 .await;
         }
     }
@@ -75,33 +83,62 @@ pub(crate) async fn handle_component(
     // uh oh.
 
     if let Some(right_question) = right_question {
-        println!("Wrong question was answered by {}", press.user.id);
+        tracing::warn!(expected = right_question, "Wrong question was answered.");
         // they are attempting the first question, this should only happen if they left
         // and rejoined (or if the bot failed to move them from the first question).
-        // TODO: at some point in the next 1000 years make a proper case that restores them
-        // though, there should be no reason that I'd have to because this is stupid to begin with.
-        // Why leave, rejoin then attempt to play the same event you tried originally?
         if index == 0 {
-            println!(
-                "{} assumed to have left and rejoined, attempting the event again.",
-                press.user.id
-            );
-            {
-                data.escape_room
-                    .write()
-                    .user_progress
-                    .remove(&press.user.id);
+            let (policy, thread) = {
+                let room = data.escape_room.read();
+                let thread = (room.room_mode == RoomMode::Threads)
+                    .then(|| room.player_threads.get(&press.user.id).map(|t| t.channel))
+                    .flatten();
+                (room.rejoin_policy, thread)
             };
-            data.write_questions().unwrap();
-        }
 
-        if !check_wrong_question_cooldown(&data, press.user.id) {
-            let _ = wrong_question_response(framework, press, right_question).await;
-        }
-        wrong_question_cooldown_handler(&data, press.user.id);
+            // the lobby stays visible in thread mode, so this is just someone pressing it again.
+            if let Some(thread) = thread {
+                press
+                    .create_response(
+                        &framework.serenity_context.http,
+                        CreateInteractionResponse::Message(
+                            CreateInteractionResponseMessage::new()
+                                .ephemeral(true)
+                                .content(format!(
+                                    "You've already moved on, head back to <#{thread}>!"
+                                )),
+                        ),
+                    )
+                    .await?;
+                return Ok(());
+            }
+
+            match policy {
+                RejoinPolicy::Reset => {
+                    tracing::info!(
+                        "Assumed to have left and rejoined, attempting the event again."
+                    );
+                    {
+                        data.escape_room
+                            .write()
+                            .user_progress
+                            .remove(&press.user.id);
+                    };
+                    data.write_questions().unwrap();
+                }
+                RejoinPolicy::Restore => {
+                    tracing::info!("Assumed to have left and rejoined, restoring their progress.");
+                    return restored_response(framework, press, right_question).await;
+                }
+            }
+        } else {
+            if !check_wrong_question_cooldown(&data, press.user.id) {
+                let _ =
+                    wrong_question_response(framework, press, index as usize + 1, right_question)
+                        .await;
+            }
+            wrong_question_cooldown_handler(&data, press.user.id);
 
-        // This *should* be the right way to handle it? check future moxy.
-        if index != 0 {
+            // This *should* be the right way to handle it? check future moxy.
             return Err(aformat!(
                 "<@{}> managed to answer the wrong question, please investigate.",
                 press.user.id.get()
@@ -113,7 +150,7 @@ pub(crate) async fn handle_component(
 
     // if its not set, it *is* possible to ignore this and continue.
     // But, bigger things could be wrong so lets just ignore.
-    let Some(q_channel) = question.channel else {
+    let Some(q_channel) = data.question_channel(press.user.id, index as usize) else {
         return Err("A channel was not found for question yet an answer has been recieved.".into());
     };
 
@@ -122,7 +159,14 @@ pub(crate) async fn handle_component(
         return Ok(());
     }
 
-    if let Some(cooldown) = check_cooldown(&data, press.user.id, index) {
+    if finishes_answer(&data, press.user.id, index, &question)
+        && let Some(cooldown) = check_cooldown(&data, press.user.id, index)
+    {
+        // they start the sequence again once the cooldown is over.
+        data.escape_room
+            .write()
+            .button_sequences
+            .remove(&(press.user.id, index));
         press
             .create_response(
                 &framework.serenity_context.http,
@@ -140,45 +184,80 @@ pub(crate) async fn handle_component(
         return Ok(());
     }
 
-    // open modal, take response, check it against the answers, done.
-    let answers = get_answer(framework.serenity_context, press.clone(), question.clone()).await;
-    println!(
-        "{} on question {} answered: {answers:?}",
-        press.user.id,
-        index + 1
-    );
+    if let QuestionKind::Review { image } = question.kind {
+        return review::submit(framework, press, &question, index as usize, image).await;
+    }
 
-    let Ok(answers) = answers else { return Ok(()) };
+    // open modal, take response, check it against the answers, done.
+    let answers = match get_answer(framework, press, question.clone(), index).await {
+        Ok(Answer::Done(answers)) => answers,
+        // part way through a button sequence.
+        Ok(Answer::Pending) => return Ok(()),
+        Err(e) => {
+            tracing::info!("No answer given: {e}");
+            return Ok(());
+        }
+    };
+    tracing::info!(?answers, "Answered.");
 
     let matches_answers = matches_answers(&answers, &question);
     if !matches_answers {
         wrong_answer_cooldown_handler(&data, press.user.id, index);
+        let content = data.escape_room.read().messages.render(
+            MessageKind::WrongAnswer,
+            &Placeholders {
+                user: Some(press.user.id),
+                question: Some(index as usize + 1),
+                channel: Some(q_channel),
+                elapsed: data.elapsed(press.user.id, now()),
+                placement: None,
+            },
+        );
         let _ = press
             .create_followup(
                 &framework.serenity_context.http,
                 CreateInteractionResponseFollowup::new()
                     .ephemeral(true)
-                    .content("That was not the right answer!"),
+                    .content(content),
             )
             .await;
     }
 
-    crate::escape_room::log::write(
-        framework.serenity_context,
-        &press.user,
-        answers,
-        index + 1,
-        log_channel,
-        matches_answers,
-    )
-    .await;
+    let attempt = Attempt {
+        user_id: press.user.id,
+        question: index + 1,
+        answers: answers.iter().map(ToString::to_string).collect(),
+        correct: matches_answers,
+        cooldown_secs: if matches_answers {
+            0
+        } else {
+            WRONG_ANSWER_COOLDOWN.as_secs()
+        },
+        attempted_at: press.id.created_at().unix_timestamp() as u64,
+    };
+    crate::escape_room::log::write(framework, &press.user, attempt, log_channel).await;
 
     if matches_answers {
-        move_to_next_channel(framework, press, q_channel).await?;
+        move_to_next_channel(framework, &Player::from_press(press), index as usize).await?;
     }
     Ok(())
 }
 
+/// If this press gives a full answer, pressing part of a button sequence doesn't.
+fn finishes_answer(data: &Data, user_id: UserId, index: u16, question: &Question) -> bool {
+    let QuestionKind::ButtonSequence { length, .. } = question.kind else {
+        return true;
+    };
+
+    let pressed = data
+        .escape_room
+        .read()
+        .button_sequences
+        .get(&(user_id, index))
+        .map_or(0, Vec::len);
+    pressed + 1 >= length
+}
+
 fn format_duration_readable(duration: std::time::Duration) -> String {
     let seconds = duration.as_secs();
 
@@ -223,7 +302,7 @@ fn matches_answers(answers: &FixedArray<FixedString<u16>>, question: &Question)
 fn checks(
     data: &Arc<Data>,
     press: &ComponentInteraction,
-) -> Result<(Question, Option<ChannelId>, Option<usize>, u16, u16), ()> {
+) -> Result<(Question, Option<ChannelId>, Option<usize>, u16), ()> {
     let room = data.escape_room.write();
     let expected_question = room.user_progress.get(&press.user.id);
 
@@ -232,7 +311,12 @@ fn checks(
         return Err(());
     }
 
-    let custom_id = press.data.custom_id.as_str();
+    // button sequences have the buttons index on the end.
+    let custom_id = press
+        .data
+        .custom_id
+        .split_once('#')
+        .map_or(press.data.custom_id.as_str(), |(id, _)| id);
     let q = room
         .questions
         .iter()
@@ -254,28 +338,21 @@ fn checks(
     room.write_questions().unwrap();
 
     #[allow(clippy::cast_possible_truncation)]
-    Ok((
-        question.clone(),
-        log_channel,
-        right_question,
-        index as u16,
-        room.questions.len() as u16,
-    ))
+    Ok((question.clone(), log_channel, right_question, index as u16))
 }
 
 async fn wrong_question_response(
     framework: FrameworkContext<'_>,
     press: &ComponentInteraction,
+    answered: usize,
     right_question: usize,
 ) -> Result<(), Error> {
     // I could just pass the right questions channel but i didn't think of that so I'm grabbing it here.
-    let right_channel = {
-        let data = framework.user_data();
-        let room = data.escape_room.read();
-        room.questions.get(right_question - 1).map(|q| q.channel)
-    };
+    let right_channel = framework
+        .user_data()
+        .question_channel(press.user.id, right_question - 1);
     // could not find question at index
-    let Some(Some(right_channel)) = right_channel else {
+    let Some(right_channel) = right_channel else {
         return Err(format!(
             "<@{}> stumbled into the wrong question and somehow we couldn't find the right one.",
             press.user.id
@@ -298,60 +375,269 @@ async fn wrong_question_response(
         )
         .await?;
 
-    let channel = { framework.user_data().escape_room.read().error_channel };
-
-    if let Some(channel) = channel {
-        let author =
-            serenity::CreateEmbedAuthor::new(press.user.name.clone()).icon_url(press.user.face());
-        let footer = serenity::CreateEmbedFooter::new(format!("UserId: {}", press.user.id));
+    let author =
+        serenity::CreateEmbedAuthor::new(press.user.name.clone()).icon_url(press.user.face());
+    let footer = serenity::CreateEmbedFooter::new(format!("UserId: {}", press.user.id));
+
+    // answering ahead of where they are means they can see channels they shouldn't.
+    let (kind, description) = if answered > right_question {
+        (
+            AlertKind::SuspectedCheating,
+            format!(
+                "Somebody answered a question ahead of where they are, they either have \
+                 Administrator or a permission override they shouldn't have.\nThey answered \
+                 <#{}> (question {answered}) when they are supposed to answer <#{}> (question \
+                 {right_question})",
+                press.channel_id, right_channel
+            ),
+        )
+    } else {
         // TODO: rejoin perm fix.
-        let description = format!(
-            "Somebody answered the wrong question either because I fucked up/they clicked the \
-             modal AGAIN before I moved them, Discord fucked up or they have Administrator.\nThey \
-             answered <#{}> when they are supposed to answer <#{}>",
-            press.channel_id, right_channel
-        );
-        let embed = serenity::CreateEmbed::new()
-            .author(author)
-            .footer(footer)
-            .description(description);
+        (
+            AlertKind::WrongQuestion,
+            format!(
+                "Somebody answered the wrong question either because I fucked up/they clicked \
+                 the modal AGAIN before I moved them, Discord fucked up or they have \
+                 Administrator.\nThey answered <#{}> when they are supposed to answer <#{}>",
+                press.channel_id, right_channel
+            ),
+        )
+    };
+    let embed = serenity::CreateEmbed::new()
+        .author(author)
+        .footer(footer)
+        .description(description);
+
+    notify::send(
+        &framework.serenity_context.http,
+        &framework.user_data(),
+        Alert::new(kind, press.user.id, String::new()).embed(embed),
+    )
+    .await;
 
-        channel
-            .send_message(
-                &framework.serenity_context.http,
-                serenity::CreateMessage::new()
-                    // Lilith, Ruben and Phil
-                    .content("<@158567567487795200> <@291089948709486593> <@101090238067113984>")
-                    .embed(embed),
-            )
-            .await?;
+    Ok(())
+}
+
+/// Points a player that rejoined back to where they were, giving them their role back.
+async fn restored_response(
+    framework: FrameworkContext<'_>,
+    press: &ComponentInteraction,
+    right_question: usize,
+) -> Result<(), Error> {
+    if let Some(guild_id) = press.guild_id {
+        restore_roles(framework, guild_id, press.user.id).await?;
     }
 
+    let right_channel = framework
+        .user_data()
+        .question_channel(press.user.id, right_question - 1);
+
+    let content = if let Some(right_channel) = right_channel {
+        format!("Your progress has been restored, head back to <#{right_channel}>!")
+    } else {
+        "Your progress has been restored!".to_owned()
+    };
+
+    press
+        .create_response(
+            &framework.serenity_context.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .ephemeral(true)
+                    .content(content),
+            ),
+        )
+        .await?;
+
     Ok(())
 }
 
+/// What a press gave back from the player.
+enum Answer {
+    Done(FixedArray<FixedString<u16>>),
+    /// Part of a button sequence, there is nothing to check yet.
+    Pending,
+}
+
 async fn get_answer(
-    ctx: &serenity::Context,
+    framework: FrameworkContext<'_>,
+    press: &ComponentInteraction,
+    question: Question,
+    index: u16,
+) -> Result<Answer, Error> {
+    let ctx = framework.serenity_context;
+    match &question.kind {
+        QuestionKind::Modal => Ok(Answer::Done(
+            modal_answer(framework, press.clone(), question).await?,
+        )),
+        QuestionKind::Select { options, .. } => {
+            let ComponentInteractionDataKind::StringSelect { values } = &press.data.kind else {
+                return Err("Expected a select menu to be used.".into());
+            };
+
+            // keep them in the listed order so it doesn't matter what order they're picked in.
+            let picked: Vec<&str> = options
+                .iter()
+                .map(String::as_str)
+                .filter(|o| values.iter().any(|v| v.as_str() == *o))
+                .collect();
+
+            press
+                .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+                .await?;
+
+            Ok(Answer::Done(single_answer(&picked.join(", "))))
+        }
+        QuestionKind::ButtonSequence { buttons, length } => {
+            sequence_answer(framework, press, buttons, *length, index).await
+        }
+        QuestionKind::Review { .. } => Err("Review questions aren't checked automatically.".into()),
+    }
+}
+
+/// Collects the answer to every part of a question.
+///
+/// Discord only allows five inputs in a modal, so longer questions are split up into pages with a
+/// button to open the next one.
+pub(super) async fn modal_answer(
+    framework: FrameworkContext<'_>,
     press: ComponentInteraction,
     question: Question,
 ) -> Result<FixedArray<FixedString<u16>>, Error> {
-    let mut modal = CreateQuickModal::new("Question").timeout(std::time::Duration::from_secs(60));
+    let ctx = framework.serenity_context;
+    let timeout = Duration::from_secs(
+        framework
+            .user_data()
+            .escape_room
+            .read()
+            .modal_timeout
+            .unwrap_or(DEFAULT_MODAL_TIMEOUT),
+    );
+
+    let pages: Vec<_> = question.parts.chunks(MODAL_INPUTS).collect();
+    let mut inputs = Vec::with_capacity(question.parts.len());
+    let mut press = press;
+
+    for (i, page) in pages.iter().enumerate() {
+        let title = if pages.len() == 1 {
+            "Question".to_owned()
+        } else {
+            format!("Question ({}/{})", i + 1, pages.len())
+        };
+
+        let mut modal = CreateQuickModal::new(title).timeout(timeout);
+        for part in *page {
+            if part.paragraph {
+                modal = modal.paragraph_field(part.content.clone());
+            } else {
+                modal = modal.short_field(part.content.clone());
+            }
+        }
+
+        let Some(response) = press.quick_modal(ctx, modal).await? else {
+            return Err("Empty response".into());
+        };
+        inputs.extend(response.inputs.iter().cloned());
+
+        if i + 1 == pages.len() {
+            // close modal.
+            response
+                .interaction
+                .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+                .await?;
+            break;
+        }
+
+        // a modal can't open another modal, so they need to press something first.
+        let next_id = format!("{}-next", response.interaction.id);
+        let components = [CreateActionRow::Buttons(Cow::Owned(vec![
+            CreateButton::new(next_id.clone()).label("Next"),
+        ]))];
+        response
+            .interaction
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(format!("Part {} of {} saved.", i + 1, pages.len()))
+                        .components(&components),
+                ),
+            )
+            .await?;
 
-    for part in question.parts {
-        modal = modal.short_field(part.content);
+        let Some(next) = ComponentInteractionCollector::new(ctx.shard.clone())
+            .author_id(press.user.id)
+            .filter(move |p| p.data.custom_id.as_str() == next_id)
+            .timeout(timeout)
+            .await
+        else {
+            return Err("Empty response".into());
+        };
+        press = next;
     }
 
-    let response = press.quick_modal(ctx, modal).await?;
+    Ok(FixedArray::from_vec_trunc(inputs))
+}
 
-    let Some(response) = response else {
-        return Err("Empty response".into());
+/// Adds the pressed button to the players sequence, only giving an answer once it is long enough.
+async fn sequence_answer(
+    framework: FrameworkContext<'_>,
+    press: &ComponentInteraction,
+    buttons: &[String],
+    length: usize,
+    index: u16,
+) -> Result<Answer, Error> {
+    let http = &framework.serenity_context.http;
+    let Some(pressed) = press
+        .data
+        .custom_id
+        .rsplit_once('#')
+        .and_then(|(_, i)| i.parse::<usize>().ok())
+        .and_then(|i| buttons.get(i))
+    else {
+        return Err("Unknown button in sequence.".into());
     };
 
-    // close modal.
-    response
-        .interaction
-        .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
-        .await?;
+    let key = (press.user.id, index);
+    let sequence = {
+        let data = framework.user_data();
+        let mut room = data.escape_room.write();
+        let sequence = room.button_sequences.entry(key).or_default();
+        sequence.push(pressed.clone());
+
+        if sequence.len() < length {
+            Err(sequence.len())
+        } else {
+            Ok(room.button_sequences.remove(&key).unwrap_or_default())
+        }
+    };
+
+    match sequence {
+        Ok(sequence) => {
+            press
+                .create_response(http, CreateInteractionResponse::Acknowledge)
+                .await?;
+
+            Ok(Answer::Done(single_answer(&sequence.join(", "))))
+        }
+        Err(pressed_count) => {
+            press
+                .create_response(
+                    http,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .ephemeral(true)
+                            .content(format!("Pressed **{pressed}** ({pressed_count}/{length})")),
+                    ),
+                )
+                .await?;
+
+            Ok(Answer::Pending)
+        }
+    }
+}
 
-    Ok(response.inputs)
+fn single_answer(answer: &str) -> FixedArray<FixedString<u16>> {
+    FixedArray::from_vec_trunc(vec![FixedString::from_str_trunc(answer)])
 }
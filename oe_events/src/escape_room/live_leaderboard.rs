@@ -0,0 +1,38 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use crate::FrameworkContext;
+
+/// How often the leaderboard can be edited, changes in between are shown together.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(15);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Starts keeping the live leaderboard up to date, does nothing if it's already running.
+pub fn start(framework: FrameworkContext<'_>) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let data = framework.user_data();
+    let http = framework.serenity_context.http.clone();
+
+    // picks the stored message back up after a restart.
+    data.mark_leaderboard_dirty();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(UPDATE_INTERVAL).await;
+
+            let dirty = std::mem::take(&mut data.escape_room.write().live_leaderboard_dirty);
+            if !dirty {
+                continue;
+            }
+
+            if let Err(e) = data.update_live_leaderboard(&http).await {
+                tracing::error!("Couldn't update the live leaderboard: {e}");
+            }
+        }
+    });
+}
@@ -1,35 +1,40 @@
-use crate::Error;
-use serenity::all::{
-    ChannelId, Colour, Context, CreateEmbed, CreateEmbedAuthor, CreateMessage, User, UserId,
-};
-use small_fixed_array::{FixedArray, FixedString};
+use crate::{Error, FrameworkContext};
+use oe_core::attempts::Attempt;
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedAuthor, CreateMessage, User};
 use std::fmt::Write;
 use tokio::{fs::OpenOptions, io::AsyncWriteExt};
 
+/// Records an attempt in the database, posting it in the log channel and mirroring it to
+/// `answers_log.jsonl` if the room wants it.
 pub async fn write(
-    ctx: &Context,
+    framework: FrameworkContext<'_>,
     user: &User,
-    answers: FixedArray<FixedString<u16>>,
-    q_num: u16,
+    attempt: Attempt,
     log_channel: Option<ChannelId>,
-    correct: bool,
 ) {
+    let data = framework.user_data();
+    if let Err(e) = data.record_attempt(&attempt).await {
+        tracing::error!(user_id = %attempt.user_id, "Couldn't record the attempt: {e}");
+    }
+
+    let mirror = data.escape_room.read().mirror_answers_log;
     let msg = QuestionLogMessage {
-        user: user.id,
-        answers,
-        q_num: q_num.to_string(),
-        correct,
+        room: data.room_name(),
+        attempt,
     };
 
-    let log_msg = serde_json::to_string(&msg).unwrap();
+    if mirror {
+        let log_msg = serde_json::to_string(&msg).unwrap();
+        let _ = create_or_push_line(&log_msg).await;
+    }
 
     if let Some(channel) = log_channel {
-        let _ = tokio::join!(
-            create_or_push_line(&log_msg),
-            channel.send_message(&ctx.http, CreateMessage::new().embed(msg.to_embed(user)))
-        );
-    } else {
-        let _ = create_or_push_line(&log_msg).await;
+        let _ = channel
+            .send_message(
+                &framework.serenity_context.http,
+                CreateMessage::new().embed(msg.to_embed(user)),
+            )
+            .await;
     }
 }
 
@@ -49,22 +54,21 @@ async fn create_or_push_line(line: &str) -> Result<(), Error> {
 
 #[derive(serde::Serialize)]
 pub struct QuestionLogMessage {
-    user: UserId,
-    answers: FixedArray<FixedString<u16>>,
-    q_num: String,
-    correct: bool,
+    room: String,
+    #[serde(flatten)]
+    attempt: Attempt,
 }
 
 impl QuestionLogMessage {
     pub fn to_embed(&self, user: &User) -> CreateEmbed<'_> {
-        let (title, colour) = if self.correct {
+        let (title, colour) = if self.attempt.correct {
             (
-                format!("Question {} answered correctly", self.q_num),
+                format!("Question {} answered correctly", self.attempt.question),
                 Colour::DARK_GREEN,
             )
         } else {
             (
-                format!("Question {} answered incorrectly", self.q_num),
+                format!("Question {} answered incorrectly", self.attempt.question),
                 Colour::RED,
             )
         };
@@ -72,7 +76,7 @@ impl QuestionLogMessage {
         let author = CreateEmbedAuthor::new(user.name.clone()).icon_url(user.face());
 
         let mut answer_str = String::new();
-        for answer in &self.answers {
+        for answer in &self.attempt.answers {
             writeln!(answer_str, "**Answer**: {answer}").unwrap();
         }
 
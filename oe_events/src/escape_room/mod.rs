@@ -1,25 +1,147 @@
-use serenity::all::{Member, UserId};
+use oe_core::structs::RejoinPolicy;
+use serenity::all::{CreateAllowedMentions, CreateMessage, GuildId, Member, RoleId, UserId};
 
+use crate::Error;
+
+mod badges;
 mod cooldown;
 pub(super) mod interaction;
+pub(super) mod live_leaderboard;
 mod log;
 mod move_channel;
+mod notify;
+pub(super) mod review;
+pub(super) mod role_queue;
+mod standings;
+mod time_limit;
 
-pub fn member_join(framework: crate::FrameworkContext<'_>, member: &Member) {
+pub async fn member_join(framework: crate::FrameworkContext<'_>, member: &Member) {
     let data = framework.user_data();
-    {
-        data.escape_room
-            .write()
-            .user_progress
-            .remove(&member.user.id);
+    let (policy, question_roles) = {
+        let room = data.escape_room.read();
+        if room.guild != Some(member.guild_id) {
+            return;
+        }
+
+        let question_roles: Vec<RoleId> = room.questions.iter().filter_map(|q| q.role_id).collect();
+        (room.rejoin_policy, question_roles)
     };
-    data.write_questions().unwrap();
+
+    let user_id = member.user.id;
+    match policy {
+        RejoinPolicy::Reset => {
+            let had_progress = {
+                data.escape_room
+                    .write()
+                    .user_progress
+                    .remove(&user_id)
+                    .is_some()
+            };
+            data.mark_leaderboard_dirty();
+            data.write_questions().unwrap();
+
+            // only bother if a role persisting bot gave them back.
+            for role in member.roles.iter().filter(|r| question_roles.contains(r)) {
+                let _ = framework
+                    .serenity_context
+                    .http
+                    .remove_member_role(
+                        member.guild_id,
+                        user_id,
+                        *role,
+                        Some("User rejoined, resetting escape room progress."),
+                    )
+                    .await;
+            }
+
+            if had_progress {
+                log_rejoin(
+                    framework,
+                    format!("<@{user_id}> rejoined, their escape room progress was reset."),
+                )
+                .await;
+            }
+        }
+        RejoinPolicy::Restore => {
+            let progress = data.escape_room.read().user_progress.get(&user_id).copied();
+            let Some(progress) = progress else {
+                return;
+            };
+
+            let message = match restore_roles(framework, member.guild_id, user_id).await {
+                Ok(()) => format!(
+                    "<@{user_id}> rejoined, their progress on question {progress} was restored."
+                ),
+                Err(e) => format!(
+                    "<@{user_id}> rejoined but I couldn't restore their progress on question \
+                     {progress}: {e}"
+                ),
+            };
+            log_rejoin(framework, message).await;
+        }
+    }
 }
 
 pub fn member_leave(framework: crate::FrameworkContext<'_>, user_id: UserId) {
     let data = framework.user_data();
+    if data.escape_room.read().rejoin_policy == RejoinPolicy::Restore {
+        return;
+    }
+
     {
         data.escape_room.write().user_progress.remove(&user_id);
     };
+    data.mark_leaderboard_dirty();
     data.write_questions().unwrap();
 }
+
+/// Gives a user back the role for where they currently are in the escape room.
+pub(super) async fn restore_roles(
+    framework: crate::FrameworkContext<'_>,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    let data = framework.user_data();
+    let http = &framework.serenity_context.http;
+
+    let thread = data
+        .escape_room
+        .read()
+        .player_threads
+        .get(&user_id)
+        .map(|t| t.channel);
+    if let Some(thread) = thread {
+        thread.add_thread_member(http, user_id).await?;
+    }
+
+    // the first question doesn't have a role.
+    let Some(role) = data.expected_role(user_id) else {
+        return Ok(());
+    };
+
+    http.add_member_role(
+        guild_id,
+        user_id,
+        role,
+        Some("User rejoined, restoring escape room progress."),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn log_rejoin(framework: crate::FrameworkContext<'_>, message: String) {
+    tracing::info!("{message}");
+
+    let channel = framework.user_data().escape_room.read().analytics_channel;
+    if let Some(channel) = channel {
+        let _ = channel
+            .send_message(
+                &framework.serenity_context.http,
+                CreateMessage::new()
+                    .content(message)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await;
+    }
+}
@@ -1,46 +1,129 @@
-use ::serenity::all::{CreateAllowedMentions, CreateMessage};
+use oe_core::{
+    messages::send_question,
+    now,
+    structs::{AlertKind, PendingRoleChange, PlayerThread, Question, RoomMode},
+    templates::{self, MessageKind, Placeholders},
+};
 use poise::serenity_prelude::{
-    ChannelId, ComponentInteraction, CreateInteractionResponseFollowup, GuildId, RoleId, UserId,
+    ChannelId, ChannelType, ComponentInteraction, CreateInteractionResponseFollowup, CreateMessage,
+    CreateThread, GuildId, RoleId, User, UserId,
 };
 
+use super::notify::{self, Alert};
+use super::role_queue::{Outcome, change_roles};
+use super::{badges, standings};
 use crate::{Error, FrameworkContext};
 
+/// A player being moved along, and how to tell them about it.
+pub struct Player<'a> {
+    pub guild_id: GuildId,
+    pub user: &'a User,
+    /// The interaction they answered with, they get a direct message if there isn't one.
+    press: Option<&'a ComponentInteraction>,
+}
+
+impl<'a> Player<'a> {
+    /// A player that answered through `press`.
+    pub fn from_press(press: &'a ComponentInteraction) -> Self {
+        Player {
+            guild_id: press.guild_id.unwrap(),
+            user: &press.user,
+            press: Some(press),
+        }
+    }
+
+    /// A player that is being moved along by somebody else.
+    pub fn new(guild_id: GuildId, user: &'a User) -> Self {
+        Player {
+            guild_id,
+            user,
+            press: None,
+        }
+    }
+
+    async fn tell(&self, framework: FrameworkContext<'_>, content: String) {
+        let http = &framework.serenity_context.http;
+        if let Some(press) = self.press {
+            let _ = press
+                .create_followup(
+                    http,
+                    CreateInteractionResponseFollowup::new()
+                        .ephemeral(true)
+                        .content(content),
+                )
+                .await;
+        } else {
+            let _ = self
+                .user
+                .id
+                .direct_message(http, CreateMessage::new().content(content))
+                .await;
+        }
+    }
+}
+
 pub async fn move_to_next_channel(
     framework: FrameworkContext<'_>,
-    press: &ComponentInteraction,
-    q_channel: ChannelId,
+    player: &Player<'_>,
+    index: usize,
 ) -> Result<(), Error> {
-    let (next_question, remove_role) = {
+    let (next_question, remove_role, room_mode, success_text) = {
         let data = framework.user_data();
         let room = data.escape_room.read();
-        let mut next_question = None;
-        let mut remove_role = None;
-
-        if let Some(index) = room
-            .questions
-            .iter()
-            .position(|q| q.channel == Some(q_channel))
-        {
-            // less than ideal code but it works at least.
-            let question = &room.questions[index];
-            remove_role = question.role_id;
-
-            if index + 1 < room.questions.len() {
-                next_question = Some(room.questions[index + 1].clone());
-            }
-        }
+        let question = room.questions.get(index);
+        let remove_role = question.and_then(|q| q.role_id);
+        let success_text = question.and_then(|q| q.success_text.clone());
+        let next_question = room.questions.get(index + 1).cloned();
+
+        (next_question, remove_role, room.room_mode, success_text)
+    };
 
-        (next_question, remove_role)
+    let mut values = Placeholders {
+        user: Some(player.user.id),
+        question: Some(index + 1),
+        elapsed: framework.user_data().elapsed(player.user.id, now()),
+        ..Default::default()
     };
 
     let Some(next_question) = next_question else {
-        println!("{} won.", press.user.id);
-        win(framework, press, remove_role).await?;
+        tracing::info!(user_id = %player.user.id, "Won the escape room.");
+        win(
+            framework,
+            player,
+            remove_role,
+            success_text.as_deref(),
+            values,
+        )
+        .await?;
         return Ok(());
     };
 
+    if room_mode == RoomMode::Threads {
+        #[allow(clippy::cast_possible_truncation)]
+        let thread = send_to_thread(framework, player, &next_question, index as u16 + 2).await?;
+
+        values.channel = Some(thread);
+        player
+            .tell(
+                framework,
+                correct_message(framework, success_text.as_deref(), &values),
+            )
+            .await;
+
+        let data = framework.user_data();
+        let question = data.user_next_question(player.user.id).await;
+        badges::reached_question(
+            &framework.serenity_context.http,
+            &data,
+            player.user.id,
+            question,
+        )
+        .await;
+        return Ok(());
+    }
+
     let Some(add_role) = next_question.role_id else {
-        println!("A role is missing, its impossible to proceed safely.");
+        tracing::warn!("A role is missing, its impossible to proceed safely.");
         return Ok(());
     };
 
@@ -48,103 +131,203 @@ pub async fn move_to_next_channel(
         return Err(format!("Could not find a channel for {next_question:?}").into());
     };
 
-    let _ = press
-        .create_followup(
-            &framework.serenity_context.http,
-            CreateInteractionResponseFollowup::new()
-                .ephemeral(true)
-                .content(format!(
-                    "That was the correct answer, please proceed to <#{next_channel}>!"
-                )),
-        )
-        .await;
-
-    handle_overwrite(
+    values.channel = Some(next_channel);
+    let outcome = handle_overwrite(
         framework,
-        press.guild_id.unwrap(),
-        press.user.id,
+        player.guild_id,
+        player.user.id,
         remove_role,
         add_role,
     )
-    .await?;
+    .await;
+
+    let content = match outcome {
+        Outcome::Done => correct_message(framework, success_text.as_deref(), &values),
+        // they can't see the next channel yet, the queue lets them know once they can.
+        Outcome::Retry => "That was the correct answer! Your roles couldn't be updated yet, \
+                           you'll get a message once you can move on."
+            .to_owned(),
+        Outcome::Failed => "That was the correct answer! Your roles couldn't be updated, staff \
+                            have been notified and will move you along."
+            .to_owned(),
+    };
+    player.tell(framework, content).await;
 
     Ok(())
 }
 
+/// The rooms correct answer message, followed by the questions success text if it has any.
+fn correct_message(
+    framework: FrameworkContext<'_>,
+    success_text: Option<&str>,
+    values: &Placeholders,
+) -> String {
+    let data = framework.user_data();
+    let room = data.escape_room.read();
+    with_success_text(
+        room.messages.render(MessageKind::Correct, values),
+        success_text,
+        values,
+    )
+}
+
+fn with_success_text(message: String, success_text: Option<&str>, values: &Placeholders) -> String {
+    match success_text {
+        Some(text) => format!("{message}\n\n{}", templates::render(text, values)),
+        None => message,
+    }
+}
+
+/// Replaces the question in the players thread, making the thread if they don't have one yet.
+async fn send_to_thread(
+    framework: FrameworkContext<'_>,
+    player: &Player<'_>,
+    question: &Question,
+    question_number: u16,
+) -> Result<ChannelId, Error> {
+    let http = &framework.serenity_context.http;
+    let data = framework.user_data();
+    let user_id = player.user.id;
+
+    let (existing, lobby) = {
+        let room = data.escape_room.read();
+        (
+            room.player_threads.get(&user_id).cloned(),
+            room.questions.first().and_then(|q| q.channel),
+        )
+    };
+
+    let thread = if let Some(existing) = existing {
+        for message in existing.messages {
+            let _ = existing.channel.delete_message(http, message, None).await;
+        }
+        existing.channel
+    } else {
+        let Some(lobby) = lobby else {
+            return Err("The first question doesn't have a channel to make threads in!".into());
+        };
+
+        let name = format!("{}'s escape room", player.user.name);
+        lobby
+            .create_thread(
+                http,
+                CreateThread::new(name)
+                    .kind(ChannelType::PrivateThread)
+                    .invitable(false),
+            )
+            .await?
+            .id
+    };
+
+    // they may have left and rejoined since the thread was made.
+    thread.add_thread_member(http, user_id).await?;
+
+    let messages = send_question(http, thread, question, question_number).await?;
+    {
+        let mut room = data.escape_room.write();
+        room.player_threads.insert(
+            user_id,
+            PlayerThread {
+                channel: thread,
+                messages,
+            },
+        );
+        room.write_questions().unwrap();
+    }
+
+    Ok(thread)
+}
+
 /// A function for winning that I would honestly like all in one function but the code sucks
 /// elsewhere.
 async fn win(
     framework: FrameworkContext<'_>,
-    press: &ComponentInteraction,
+    player: &Player<'_>,
     remove_role: Option<RoleId>,
+    success_text: Option<&str>,
+    mut values: Placeholders,
 ) -> Result<(), Error> {
-    let guild_id = press.guild_id.unwrap();
+    let guild_id = player.guild_id;
     // get room.
     let data = framework.user_data();
     let http = &framework.serenity_context.http;
-    let user_id = press.user.id;
-    let (channel_id, first, first_winner_role, winner_role) = {
+    let user_id = player.user.id;
+    let finished_at = now();
+    let (channel_id, placement, role, messages, announcement, missing_start) = {
         let mut room = data.escape_room.write();
 
-        let first = room.winners.first_winner.is_none();
+        // only set once the last answer is right, so it is the players finish time.
+        let missing_start = match room.start_end_time.get_mut(&user_id) {
+            Some((_, end)) => {
+                *end = Some(finished_at);
+                false
+            }
+            None => true,
+        };
+
         room.winners.winners.push(user_id);
+        room.live_leaderboard_dirty = true;
         room.winners.first_winner.get_or_insert(user_id);
+        let placement = room.winners.winners.len();
 
         (
             room.winners.winner_channel,
-            first,
-            room.winners.first_winner_role,
-            room.winners.winner_role,
+            placement,
+            room.winners.role_for(placement),
+            room.messages.clone(),
+            room.winners
+                .tier(placement)
+                .and_then(|t| t.announcement.clone()),
+            missing_start,
         )
     };
-
-    let (Some(first_winner_role), Some(winner_role)) = (first_winner_role, winner_role) else {
-        println!("Unable to win, roles are missing.");
-        return Ok(());
-    };
-
     // this is here to prevent deadlocks.
-    if first {
-        data.write_questions().unwrap();
-        let _ = handle_overwrite(
-            framework,
-            guild_id,
-            press.user.id,
-            remove_role,
-            first_winner_role,
+    data.write_questions().unwrap();
+    values.placement = Some(placement);
+
+    if missing_start {
+        let content = format!(
+            "<@{user_id}> finished the escape room at <t:{finished_at}> without a starting \
+             timestamp?"
+        );
+        notify::send(
+            http,
+            &data,
+            Alert::new(AlertKind::MissingStartTime, user_id, content),
         )
         .await;
-    } else {
-        data.write_questions().unwrap();
-        let _ =
-            handle_overwrite(framework, guild_id, press.user.id, remove_role, winner_role).await;
     }
 
+    badges::won(framework, user_id, placement).await;
+
+    let Some(role) = role else {
+        tracing::warn!(%user_id, "Unable to win, roles are missing.");
+        return Ok(());
+    };
+
+    handle_overwrite(framework, guild_id, user_id, remove_role, role).await;
+
     // Mirror of the above, without extra checks.
     let Some(channel_id) = channel_id else {
         return Err(format!("{user_id} could not win because there is no winning channel!").into());
     };
 
-    let _ = press
-        .create_followup(
-            &framework.serenity_context.http,
-            CreateInteractionResponseFollowup::new()
-                .ephemeral(true)
-                .content(format!("You won the escape room! <#{channel_id}>!")),
-        )
+    values.channel = Some(channel_id);
+    let won = messages.render(MessageKind::Won, &values);
+    player
+        .tell(framework, with_success_text(won, success_text, &values))
         .await;
 
-    if first {
-        channel_id
-            .say(
-                http,
-                format!("<@{user_id}> was the first to win the escape room! Congratulations!"),
-            )
-            .await?;
-    } else {
-        channel_id
-            .say(http, format!("Congratulations! <@{user_id}>"))
-            .await?;
+    let announcement = match announcement {
+        Some(announcement) => templates::render(&announcement, &values),
+        None if placement == 1 => messages.render(MessageKind::FirstWinner, &values),
+        None => messages.render(MessageKind::Winner, &values),
+    };
+    channel_id.say(http, announcement).await?;
+
+    // they've already won, so this shouldn't fail the answer.
+    if let Err(e) = standings::update(framework, channel_id).await {
+        tracing::error!("Couldn't update the escape room standings: {e}");
     }
 
     Ok(())
@@ -156,75 +339,20 @@ async fn handle_overwrite(
     user_id: UserId,
     remove_role: Option<RoleId>,
     add_role: RoleId,
-) -> Result<(), Error> {
-    let http = &framework.serenity_context.http;
-    println!("Staging addition of {add_role} for {user_id}.");
-    if http
-        .add_member_role(
-            guild_id,
-            user_id,
-            add_role,
-            Some("User moved to the next question."),
-        )
-        .await
-        .is_err()
-    {
-        handle_err(framework, user_id, remove_role, add_role).await;
-    }
-
-    if let Some(remove_role) = remove_role {
-        println!("Staging removal of {remove_role} for {user_id}.");
-        if http
-            .remove_member_role(
-                guild_id,
-                user_id,
-                remove_role,
-                Some("User moved to the next question"),
-            )
-            .await
-            .is_err()
-        {
-            handle_err(framework, user_id, Some(remove_role), add_role).await;
-        }
-    }
-
-    // move them to the right question, good for fixing perms or other stuff.
-    framework.user_data().user_next_question(user_id);
-
-    Ok(())
+) -> Outcome {
+    // progress is only moved along once the roles are sorted, good for fixing perms or other stuff.
+    let change = PendingRoleChange::new(guild_id, user_id, Some(add_role), remove_role, true);
+    change_roles(framework, change).await
 }
 
-async fn handle_err(
+/// Gives the user `add_role` and takes `remove_role`, without touching their progress.
+pub(super) async fn swap_roles(
     framework: FrameworkContext<'_>,
+    guild_id: GuildId,
     user_id: UserId,
     remove_role: Option<RoleId>,
     add_role: RoleId,
 ) {
-    let http = &framework.serenity_context.http;
-    let message = if let Some(remove_role) = remove_role {
-        format!(
-            "<@101090238067113984> <@291089948709486593> <@158567567487795200> I couldn't modify \
-             the roles properly. Please make sure <@{user_id}> gets <@&{remove_role}> removed and \
-             <@&{add_role}> added!"
-        )
-    } else {
-        format!(
-            "<@101090238067113984> <@291089948709486593> <@158567567487795200> I couldn't modify \
-             the roles properly. Please make sure <@{user_id}> gets <@&{add_role}> added!"
-        )
-    };
-
-    let error_channel = framework.user_data().escape_room.read().error_channel;
-    if let Some(e_channel) = error_channel {
-        println!("Couldn't resolve permissions for User: {user_id}");
-        // ping Phil, Ruben and James about the fuckup
-        let _ = e_channel
-            .send_message(
-                http,
-                CreateMessage::new()
-                    .content(message)
-                    .allowed_mentions(CreateAllowedMentions::new().roles(&[]).all_users(true)),
-            )
-            .await;
-    }
+    let change = PendingRoleChange::new(guild_id, user_id, Some(add_role), remove_role, false);
+    change_roles(framework, change).await;
 }
@@ -0,0 +1,90 @@
+use oe_core::now;
+use oe_core::structs::{AlertKind, Data};
+use poise::serenity_prelude::{CreateAllowedMentions, CreateEmbed, CreateMessage, Http};
+
+const DEFAULT_DEDUP_SECONDS: u64 = 300;
+
+/// Something staff should know about.
+pub(super) struct Alert {
+    pub kind: AlertKind,
+    /// Alerts of the same kind with the same key are deduplicated, usually the user it's about.
+    pub key: String,
+    pub content: String,
+    pub embed: Option<CreateEmbed<'static>>,
+}
+
+impl Alert {
+    pub fn new(kind: AlertKind, key: impl ToString, content: String) -> Self {
+        Alert {
+            kind,
+            key: key.to_string(),
+            content,
+            embed: None,
+        }
+    }
+
+    pub fn embed(mut self, embed: CreateEmbed<'static>) -> Self {
+        self.embed = Some(embed);
+        self
+    }
+}
+
+/// Sends an alert wherever the room routes it, unless the same one was sent recently.
+pub(super) async fn send(http: &Http, data: &Data, alert: Alert) {
+    let now = now();
+    let (route, quiet) = {
+        let mut room = data.escape_room.write();
+        let route = room.notifications.route(alert.kind, room.error_channel);
+        let dedup = route.dedup_seconds.unwrap_or(DEFAULT_DEDUP_SECONDS);
+
+        let key = (alert.kind, alert.key.clone());
+        if room
+            .sent_alerts
+            .get(&key)
+            .is_some_and(|sent| now.saturating_sub(*sent) < dedup)
+        {
+            return;
+        }
+        room.sent_alerts.insert(key, now);
+
+        let hour = ((now / 3600) % 24) as u8;
+        let quiet = room
+            .notifications
+            .quiet_hours
+            .is_some_and(|q| q.contains(hour));
+        (route, quiet)
+    };
+
+    let Some(channel) = route.channel else {
+        tracing::warn!(kind = ?alert.kind, "Nowhere to send alert: {}", alert.content);
+        return;
+    };
+
+    let users = route.users.unwrap_or_default();
+    let roles = route.roles.unwrap_or_default();
+    let mut content = alert.content;
+    let mut allowed_mentions = CreateAllowedMentions::new();
+    if !quiet {
+        let mentions: Vec<String> = users
+            .iter()
+            .map(|u| format!("<@{u}>"))
+            .chain(roles.iter().map(|r| format!("<@&{r}>")))
+            .collect();
+
+        if !mentions.is_empty() {
+            content = format!("{} {content}", mentions.join(" "));
+        }
+        allowed_mentions = allowed_mentions.users(users).roles(roles);
+    }
+
+    let mut builder = CreateMessage::new()
+        .content(content)
+        .allowed_mentions(allowed_mentions);
+    if let Some(embed) = alert.embed {
+        builder = builder.embed(embed);
+    }
+
+    if let Err(e) = channel.send_message(http, builder).await {
+        tracing::error!(kind = ?alert.kind, "Couldn't send alert: {e}");
+    }
+}
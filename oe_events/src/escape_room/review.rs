@@ -0,0 +1,368 @@
+use std::{borrow::Cow, fmt::Write, time::Duration};
+
+use oe_core::attempts::Attempt;
+use oe_core::structs::{PendingReview, Question};
+use poise::serenity_prelude::{
+    ButtonStyle, Colour, ComponentInteraction, CreateActionRow, CreateAttachment, CreateButton,
+    CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateMessage,
+    CreateQuickModal, MessageCollector, User, UserId,
+};
+
+use super::interaction::modal_answer;
+use super::move_channel::{Player, move_to_next_channel};
+use crate::{Error, FrameworkContext};
+
+/// How long players have to send their screenshot.
+const IMAGE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Sends a players submission to the review channel for staff to approve or reject.
+// interactions are always made after the unix epoch.
+#[allow(clippy::cast_sign_loss)]
+pub(super) async fn submit(
+    framework: FrameworkContext<'_>,
+    press: &ComponentInteraction,
+    question: &Question,
+    index: usize,
+    image: bool,
+) -> Result<(), Error> {
+    let http = &framework.serenity_context.http;
+    let data = framework.user_data();
+
+    let (pending, review_channel) = {
+        let room = data.escape_room.read();
+        (
+            room.reviews.iter().any(|r| r.user_id == press.user.id),
+            room.review_channel,
+        )
+    };
+
+    if pending {
+        press
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content("Your last submission is still waiting to be reviewed!"),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let Some(review_channel) = review_channel else {
+        return Err("A review question was answered but there is no review channel!".into());
+    };
+
+    let (embed, attachment, answers) = if image {
+        press
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content("Send me your screenshot in direct messages within 2 minutes!"),
+                ),
+            )
+            .await?;
+
+        let user_id = press.user.id;
+        let Some(message) = MessageCollector::new(framework.serenity_context.shard.clone())
+            .author_id(user_id)
+            .filter(|m| m.guild_id.is_none() && !m.attachments.is_empty())
+            .timeout(IMAGE_TIMEOUT)
+            .await
+        else {
+            let _ = press
+                .create_followup(
+                    http,
+                    CreateInteractionResponseFollowup::new()
+                        .ephemeral(true)
+                        .content("You took too long, press the button again to retry."),
+                )
+                .await;
+            return Ok(());
+        };
+
+        let upload = &message.attachments[0];
+        let name = upload.filename.to_string();
+        let bytes = upload.download().await?;
+
+        (
+            CreateEmbed::new().image(format!("attachment://{name}")),
+            Some(CreateAttachment::bytes(bytes, name.clone())),
+            vec![name],
+        )
+    } else {
+        let answers = modal_answer(framework, press.clone(), question.clone()).await?;
+
+        let mut description = String::new();
+        for (part, answer) in question.parts.iter().zip(&answers) {
+            writeln!(description, "**{}**\n{answer}", part.content).unwrap();
+        }
+
+        (
+            CreateEmbed::new().description(description),
+            None,
+            answers.iter().map(ToString::to_string).collect(),
+        )
+    };
+
+    let embed = embed
+        .title(format!("Question {} submission", index + 1))
+        .colour(Colour::GOLD)
+        .author(CreateEmbedAuthor::new(press.user.name.clone()).icon_url(press.user.face()))
+        .footer(CreateEmbedFooter::new(format!("UserId: {}", press.user.id)));
+
+    let components = [CreateActionRow::Buttons(Cow::Owned(vec![
+        CreateButton::new(format!("review-approve-{}-{index}", press.user.id))
+            .label("Approve")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("review-reject-{}-{index}", press.user.id))
+            .label("Reject")
+            .style(ButtonStyle::Danger),
+    ]))];
+
+    let mut builder = CreateMessage::new().embed(embed).components(&components);
+    if let Some(attachment) = attachment {
+        builder = builder.add_file(attachment);
+    }
+
+    let message = review_channel.send_message(http, builder).await?;
+
+    {
+        let mut room = data.escape_room.write();
+        room.reviews.push(PendingReview {
+            user_id: press.user.id,
+            question: index,
+            channel: review_channel,
+            message: message.id,
+            submitted_at: press.id.created_at().unix_timestamp() as u64,
+            answers,
+        });
+        room.write_questions().unwrap();
+    }
+
+    let _ = press
+        .create_followup(
+            http,
+            CreateInteractionResponseFollowup::new()
+                .ephemeral(true)
+                .content("Your submission has been sent to the event committee for review!"),
+        )
+        .await;
+
+    Ok(())
+}
+
+/// Handles the approve and reject buttons on a submission.
+pub async fn handle_decision(
+    framework: FrameworkContext<'_>,
+    press: &ComponentInteraction,
+) -> Result<(), Error> {
+    let http = &framework.serenity_context.http;
+    let data = framework.user_data();
+
+    let mut parts = press.data.custom_id.splitn(4, '-').skip(1);
+    let (Some(action), Some(user_id), Some(index)) = (
+        parts.next(),
+        parts.next().and_then(|u| u.parse::<UserId>().ok()),
+        parts.next().and_then(|i| i.parse::<usize>().ok()),
+    ) else {
+        return Ok(());
+    };
+
+    if !is_staff(framework, press) {
+        press
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content("Only staff can review submissions."),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let exists = data
+        .escape_room
+        .read()
+        .reviews
+        .iter()
+        .any(|r| r.user_id == user_id && r.question == index);
+
+    // approving takes the review straight away so two staff can't both move the player along.
+    let taken = if action == "approve" {
+        take_review(framework, user_id, index)
+    } else {
+        None
+    };
+    if !exists || (action == "approve" && taken.is_none()) {
+        press
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content("This submission has already been reviewed."),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let reviewer = press.user.id;
+    let user = user_id.to_user(framework.serenity_context).await?;
+
+    if let Some(review) = taken {
+        record_decision(framework, &user, review, true).await;
+        press
+            .create_response(
+                http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(format!("Approved by <@{reviewer}>"))
+                        .components(Vec::<CreateActionRow>::new()),
+                ),
+            )
+            .await?;
+
+        // they could have been moved along by staff in the meantime.
+        if data.get_user_question(user_id) != index + 1 {
+            return Ok(());
+        }
+
+        let Some(guild_id) = press.guild_id else {
+            return Ok(());
+        };
+
+        tracing::info!(
+            %user_id,
+            reviewer_id = %reviewer,
+            question = index + 1,
+            "Submission approved."
+        );
+        move_to_next_channel(framework, &Player::new(guild_id, &user), index).await?;
+        return Ok(());
+    }
+
+    let modal = CreateQuickModal::new("Reject submission")
+        .timeout(Duration::from_secs(300))
+        .paragraph_field("Reason");
+
+    let Some(response) = press.quick_modal(framework.serenity_context, modal).await? else {
+        return Ok(());
+    };
+
+    let reason = response
+        .inputs
+        .first()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+
+    let Some(review) = take_review(framework, user_id, index) else {
+        response
+            .interaction
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content("This submission has already been reviewed."),
+                ),
+            )
+            .await?;
+        return Ok(());
+    };
+    record_decision(framework, &user, review, false).await;
+
+    response
+        .interaction
+        .create_response(
+            http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(format!("Rejected by <@{reviewer}>: {reason}"))
+                    .components(Vec::<CreateActionRow>::new()),
+            ),
+        )
+        .await?;
+
+    tracing::info!(
+        %user_id,
+        reviewer_id = %reviewer,
+        question = index + 1,
+        "Submission rejected."
+    );
+    let _ = user
+        .id
+        .direct_message(
+            http,
+            CreateMessage::new().content(format!(
+                "Your submission for question {} was rejected: {reason}\nYou can submit again \
+                 whenever you are ready.",
+                index + 1
+            )),
+        )
+        .await;
+
+    Ok(())
+}
+
+/// Removes the pending review, returns None if someone else already took it.
+fn take_review(
+    framework: FrameworkContext<'_>,
+    user_id: UserId,
+    index: usize,
+) -> Option<PendingReview> {
+    let data = framework.user_data();
+    let mut room = data.escape_room.write();
+    let position = room
+        .reviews
+        .iter()
+        .position(|r| r.user_id == user_id && r.question == index)?;
+
+    let review = room.reviews.remove(position);
+    room.write_questions().unwrap();
+    Some(review)
+}
+
+/// Records the decision as an attempt, so reviewed questions are counted like any other.
+async fn record_decision(
+    framework: FrameworkContext<'_>,
+    user: &User,
+    review: PendingReview,
+    approved: bool,
+) {
+    let log_channel = framework.user_data().escape_room.read().analytics_channel;
+    #[allow(clippy::cast_possible_truncation)]
+    let attempt = Attempt {
+        user_id: review.user_id,
+        question: review.question as u16 + 1,
+        answers: review.answers,
+        correct: approved,
+        // staff decide when they can try again.
+        cooldown_secs: 0,
+        attempted_at: review.submitted_at,
+    };
+    crate::escape_room::log::write(framework, user, attempt, log_channel).await;
+}
+
+/// Bot owners and anyone set as staff in the rooms notifications.
+fn is_staff(framework: FrameworkContext<'_>, press: &ComponentInteraction) -> bool {
+    if framework.options.owners.contains(&press.user.id) {
+        return true;
+    }
+
+    let data = framework.user_data();
+    let room = data.escape_room.read();
+    let staff = &room.notifications;
+    staff.staff_users.contains(&press.user.id)
+        || press
+            .member
+            .as_ref()
+            .is_some_and(|m| m.roles.iter().any(|r| staff.staff_roles.contains(r)))
+}
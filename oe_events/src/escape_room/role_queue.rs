@@ -0,0 +1,243 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use oe_core::now;
+use oe_core::structs::{AlertKind, Data, PendingRoleChange};
+use poise::serenity_prelude::{self as serenity, CreateMessage, Http, HttpError};
+
+use super::badges;
+use super::notify::{self, Alert};
+
+use crate::FrameworkContext;
+
+/// How many times a role change is attempted before staff are told about it.
+const MAX_ATTEMPTS: u8 = 6;
+/// The delay before the first retry, doubled every attempt after.
+const BASE_DELAY_SECS: u64 = 5;
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Starts retrying queued role changes in the background, does nothing if it's already running.
+pub fn start(framework: FrameworkContext<'_>) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let data = framework.user_data();
+    let http = framework.serenity_context.http.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            process_queue(&http, &data).await;
+        }
+    });
+}
+
+/// How a role change went.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Outcome {
+    Done,
+    /// Discord had a problem, it might go through later.
+    Retry,
+    /// Retrying won't help, like missing permissions or the member leaving.
+    Failed,
+}
+
+/// Attempts a role change, queueing it up to be retried if it doesn't go through.
+///
+/// Staff have already been told about it if this returns [`Outcome::Failed`].
+pub(super) async fn change_roles(
+    framework: FrameworkContext<'_>,
+    mut change: PendingRoleChange,
+) -> Outcome {
+    let http = &framework.serenity_context.http;
+    let data = framework.user_data();
+
+    match attempt(http, &data, &mut change).await {
+        Outcome::Done => {
+            if change.advance {
+                let question = data.user_next_question(change.user_id).await;
+                badges::reached_question(http, &data, change.user_id, question).await;
+            }
+            return Outcome::Done;
+        }
+        Outcome::Failed => {
+            handle_err(http, &data, &change).await;
+            return Outcome::Failed;
+        }
+        Outcome::Retry => {}
+    }
+
+    tracing::warn!(
+        user_id = %change.user_id,
+        "Couldn't change roles, queueing a retry."
+    );
+    change.attempts = 1;
+    change.retry_at = now() + BASE_DELAY_SECS;
+
+    let mut room = data.escape_room.write();
+    change.id = room.role_queue.iter().map(|c| c.id).max().unwrap_or(0) + 1;
+    room.role_queue.push(change);
+    room.write_questions().unwrap();
+    Outcome::Retry
+}
+
+async fn process_queue(http: &Http, data: &Arc<Data>) {
+    let now = now();
+    let due: Vec<PendingRoleChange> = {
+        let room = data.escape_room.read();
+        room.role_queue
+            .iter()
+            .filter(|c| c.retry_at <= now)
+            .cloned()
+            .collect()
+    };
+
+    for mut change in due {
+        let (id, user_id) = (change.id, change.user_id);
+        let outcome = attempt(http, data, &mut change).await;
+
+        let give_up = outcome == Outcome::Failed
+            || (outcome == Outcome::Retry && change.attempts + 1 >= MAX_ATTEMPTS);
+        if give_up {
+            handle_err(http, data, &change).await;
+        }
+
+        {
+            let mut room = data.escape_room.write();
+            let Some(pos) = room
+                .role_queue
+                .iter()
+                .position(|c| c.id == id && c.user_id == user_id && c.guild_id == change.guild_id)
+            else {
+                continue;
+            };
+
+            if outcome == Outcome::Done || give_up {
+                room.role_queue.remove(pos);
+            } else {
+                change.attempts += 1;
+                change.retry_at = now + (BASE_DELAY_SECS << change.attempts.saturating_sub(1));
+                room.role_queue[pos] = change.clone();
+            }
+            room.write_questions().unwrap();
+        }
+
+        // staff have been asked to fix it by hand when giving up, progress is left alone.
+        if outcome == Outcome::Done && change.advance {
+            let question = data.user_next_question(user_id).await;
+            badges::reached_question(http, data, user_id, question).await;
+            let _ = user_id
+                .direct_message(
+                    http,
+                    CreateMessage::new()
+                        .content("Your roles have been sorted, you can head to the next question!"),
+                )
+                .await;
+        }
+    }
+}
+
+/// Tries to apply the change, clearing each part of it as it goes through.
+async fn attempt(http: &Http, data: &Data, change: &mut PendingRoleChange) -> Outcome {
+    let user_id = change.user_id;
+    let (added, removed) = (change.add, change.remove);
+    let mut retry = true;
+
+    if let Some(add_role) = change.add {
+        tracing::info!(%user_id, role_id = %add_role, "Staging role addition.");
+        match http
+            .add_member_role(
+                change.guild_id,
+                user_id,
+                add_role,
+                Some("User moved to the next question."),
+            )
+            .await
+        {
+            Ok(()) => change.add = None,
+            Err(e) => retry &= is_transient(&e),
+        }
+    }
+
+    if let Some(remove_role) = change.remove {
+        tracing::info!(%user_id, role_id = %remove_role, "Staging role removal.");
+        match http
+            .remove_member_role(
+                change.guild_id,
+                user_id,
+                remove_role,
+                Some("User moved to the next question"),
+            )
+            .await
+        {
+            Ok(()) => change.remove = None,
+            Err(e) => retry &= is_transient(&e),
+        }
+    }
+
+    let succeeded = change.add.is_none() && change.remove.is_none();
+    if let Err(e) = data
+        .record_role_change(user_id, added, removed, succeeded)
+        .await
+    {
+        tracing::error!(%user_id, "Couldn't record the role change: {e}");
+    }
+
+    if succeeded {
+        Outcome::Done
+    } else if retry {
+        Outcome::Retry
+    } else {
+        Outcome::Failed
+    }
+}
+
+/// Server errors and rate limits, anything else like a 403 or 404 will fail again.
+fn is_transient(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) => {
+            response.status_code.is_server_error() || response.status_code.as_u16() == 429
+        }
+        // couldn't reach discord at all.
+        serenity::Error::Http(HttpError::Request(_)) => true,
+        _ => false,
+    }
+}
+
+async fn handle_err(http: &Http, data: &Data, change: &PendingRoleChange) {
+    let user_id = change.user_id;
+    let mut fixes = Vec::with_capacity(2);
+    if let Some(remove_role) = change.remove {
+        fixes.push(format!("<@&{remove_role}> removed"));
+    }
+    if let Some(add_role) = change.add {
+        fixes.push(format!("<@&{add_role}> added"));
+    }
+
+    tracing::error!(%user_id, "Couldn't resolve roles, giving up.");
+    let mut content = format!(
+        "I couldn't modify the roles properly after {} attempts. Please make sure <@{user_id}> \
+         gets {}! (`/escape-room reconcile` can fix this in bulk)",
+        change.attempts + 1,
+        fixes.join(" and ")
+    );
+    if change.advance {
+        content.push_str(
+            "\nThey haven't been moved to the next question, use `/set-question` \
+             once the roles are fixed.",
+        );
+    }
+    notify::send(
+        http,
+        data,
+        Alert::new(AlertKind::RoleFailure, user_id, content),
+    )
+    .await;
+}
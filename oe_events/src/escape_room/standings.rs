@@ -0,0 +1,83 @@
+use std::fmt::Write;
+
+use oe_core::{messages::is_unknown_message, structs::EscapeRoom, templates::format_duration};
+use poise::serenity_prelude::{ChannelId, Colour, CreateEmbed, CreateMessage, EditMessage};
+
+use crate::{Error, FrameworkContext};
+
+/// Leaves room for the "and more" line in the embed description.
+const DESCRIPTION_LIMIT: usize = 4000;
+
+/// Edits the standings in the winner channel, posting them if they aren't there yet.
+pub(super) async fn update(
+    framework: FrameworkContext<'_>,
+    channel_id: ChannelId,
+) -> Result<(), Error> {
+    let http = &framework.serenity_context.http;
+    let data = framework.user_data();
+    let (embed, existing) = {
+        let room = data.escape_room.read();
+        (standings_embed(&room), room.winners.standings_message)
+    };
+
+    if let Some(message_id) = existing {
+        match channel_id
+            .edit_message(http, message_id, EditMessage::new().embed(embed.clone()))
+            .await
+        {
+            Ok(_) => return Ok(()),
+            // it was deleted, so post it again.
+            Err(e) if is_unknown_message(&e) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let message = channel_id
+        .send_message(http, CreateMessage::new().embed(embed))
+        .await?;
+
+    let mut room = data.escape_room.write();
+    room.winners.standings_message = Some(message.id);
+    room.write_questions().unwrap();
+    Ok(())
+}
+
+fn standings_embed(room: &EscapeRoom) -> CreateEmbed<'static> {
+    let mut description = String::new();
+    let mut current_tier = None;
+
+    for (i, user_id) in room.winners.winners.iter().enumerate() {
+        let placement = i + 1;
+
+        if let Some(tier) = room.winners.tier(placement)
+            && current_tier != Some(tier.name.as_str())
+        {
+            current_tier = Some(tier.name.as_str());
+            writeln!(description, "\n**{}**", tier.name).unwrap();
+        }
+
+        let time = room
+            .start_end_time
+            .get(user_id)
+            .and_then(|(start, end)| end.map(|end| format_duration(end.saturating_sub(*start))))
+            .map(|t| format!(" ({t})"))
+            .unwrap_or_default();
+
+        let line = format!("`#{placement}` <@{user_id}>{time}\n");
+        if description.len() + line.len() > DESCRIPTION_LIMIT {
+            let remaining = room.winners.winners.len() - i;
+            write!(description, "...and {remaining} more").unwrap();
+            break;
+        }
+        description.push_str(&line);
+    }
+
+    if description.is_empty() {
+        description.push_str("Nobody has finished yet.");
+    }
+
+    CreateEmbed::new()
+        .title("Standings")
+        .description(description)
+        .colour(Colour::GOLD)
+}
@@ -0,0 +1,51 @@
+use crate::{Error, FrameworkContext};
+use poise::serenity_prelude::{
+    ComponentInteraction, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+
+use super::move_channel::swap_roles;
+
+/// Blocks submissions from players that have used up their time.
+///
+/// The first time a player is caught they are given the time's up role, if there is one.
+/// Returns true if the submission should not go through.
+// interactions are always made after the unix epoch.
+#[allow(clippy::cast_sign_loss)]
+pub async fn check_time_limit(
+    framework: FrameworkContext<'_>,
+    press: &ComponentInteraction,
+) -> Result<bool, Error> {
+    let now = press.id.created_at().unix_timestamp() as u64;
+    let Some((limit, first_time)) = framework.user_data().check_time_limit(press.user.id, now)
+    else {
+        return Ok(false);
+    };
+
+    let content = if let Some(channel) = limit.channel {
+        format!("Your time is up! Head over to <#{channel}>.")
+    } else {
+        "Your time is up! You can no longer submit answers.".to_owned()
+    };
+
+    press
+        .create_response(
+            &framework.serenity_context.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .ephemeral(true)
+                    .content(content),
+            ),
+        )
+        .await?;
+
+    if first_time {
+        tracing::info!(user_id = %press.user.id, "Ran out of time.");
+        if let (Some(role), Some(guild_id)) = (limit.role, press.guild_id) {
+            // the question they pressed might not be the one they are on.
+            let question_role = framework.user_data().expected_role(press.user.id);
+            swap_roles(framework, guild_id, press.user.id, question_role, role).await;
+        }
+    }
+
+    Ok(true)
+}
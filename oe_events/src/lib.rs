@@ -2,6 +2,7 @@
 #![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
 use poise::serenity_prelude as serenity;
+use tracing::Instrument;
 
 pub(crate) use oe_core::structs::{Data, Error, FrameworkContext};
 mod escape_room;
@@ -12,16 +13,35 @@ pub async fn handler(
 ) -> Result<(), Error> {
     match event {
         serenity::FullEvent::Ready { data_about_bot, .. } => {
-            println!("Logged in as {}", data_about_bot.user.tag());
+            tracing::info!("Logged in as {}", data_about_bot.user.tag());
+            escape_room::role_queue::start(framework);
+            escape_room::live_leaderboard::start(framework);
         }
         serenity::FullEvent::InteractionCreate { interaction } => match interaction {
             serenity::Interaction::Component(press) => {
-                escape_room::interaction::handle_component(framework, press).await?;
+                let span = tracing::info_span!(
+                    "interaction",
+                    user_id = %press.user.id,
+                    guild_id = ?press.guild_id,
+                    custom_id = %press.data.custom_id,
+                    room = %framework.user_data().room_name(),
+                    question = tracing::field::Empty,
+                );
+
+                if press.data.custom_id.starts_with("review-") {
+                    escape_room::review::handle_decision(framework, press)
+                        .instrument(span)
+                        .await?;
+                } else {
+                    escape_room::interaction::handle_component(framework, press)
+                        .instrument(span)
+                        .await?;
+                }
             }
             _ => return Ok(()),
         },
         serenity::FullEvent::GuildMemberAddition { new_member } => {
-            escape_room::member_join(framework, new_member);
+            escape_room::member_join(framework, new_member).await;
         }
         serenity::FullEvent::GuildMemberRemoval {
             guild_id: _,
@@ -3,6 +3,11 @@
 use poise::serenity_prelude as serenity;
 use serenity::GatewayIntents;
 use std::{env, sync::Arc, time::Duration};
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
 use oe_core::structs::{Data, EscapeRoom, EventBadges};
 
@@ -14,9 +19,19 @@ pub type PrefixContext<'a> = poise::PrefixContext<'a, Data, Error>;
 pub type FrameworkContext<'a> = poise::FrameworkContext<'a, Data, Error>;
 pub type Command = poise::Command<Data, Error>;
 
+/// Used when `RUST_LOG` isn't set, keeps the libraries quiet.
+const DEFAULT_LOG_FILTER: &str =
+    "warn,events_bot=info,oe_core=info,oe_events=info,oe_commands=info";
+
 async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
     match error {
         poise::FrameworkError::Command { error, ctx, .. } => {
+            tracing::error!(
+                command = %ctx.command().qualified_name,
+                user_id = %ctx.author().id,
+                guild_id = ?ctx.guild_id(),
+                "Command failed: {error}"
+            );
             let _ = ctx.say(format!("Error in command: {error}")).await;
         }
         poise::FrameworkError::CommandCheckFailed { error, ctx, .. } => {
@@ -28,7 +43,7 @@ async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
         }
         error => {
             if let Err(e) = poise::builtins::on_error(error).await {
-                println!("Error while handling error: {e}");
+                tracing::error!("Error while handling error: {e}");
             }
         }
     }
@@ -37,8 +52,11 @@ async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
 #[tokio::main]
 async fn main() {
     let _ = dotenvy::dotenv();
+    // flushes the log file when dropped.
+    let _log_guard = init_logging();
     let token = serenity::Token::from_env("DISCORD_TOKEN").expect("missing DISCORD_TOKEN");
     let intents = GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MEMBERS
         | GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
@@ -58,6 +76,18 @@ async fn main() {
             ))),
             ..Default::default()
         },
+        pre_command: |ctx| {
+            Box::pin(async move {
+                tracing::info!(
+                    command = %ctx.command().qualified_name,
+                    user_id = %ctx.author().id,
+                    guild_id = ?ctx.guild_id(),
+                    channel_id = %ctx.channel_id(),
+                    room = %ctx.data().room_name(),
+                    "Running command."
+                );
+            })
+        },
         on_error: |error| Box::pin(on_error(error)),
         event_handler: |framework, event| Box::pin(oe_events::handler(event, framework)),
         ..Default::default()
@@ -84,6 +114,41 @@ async fn main() {
     client.unwrap().start().await.unwrap();
 }
 
+/// Logs to stdout, and to rotating files in `LOG_DIR` when it is set.
+///
+/// Levels are read from `RUST_LOG`, and `LOG_ROTATION` can be `minutely`, `hourly`, `daily` or
+/// `never`.
+fn init_logging() -> Option<WorkerGuard> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+
+    let (file_layer, guard) = match env::var("LOG_DIR") {
+        Ok(dir) => {
+            let rotation = match env::var("LOG_ROTATION").as_deref() {
+                Ok("minutely") => Rotation::MINUTELY,
+                Ok("hourly") => Rotation::HOURLY,
+                Ok("never") => Rotation::NEVER,
+                _ => Rotation::DAILY,
+            };
+            let appender = RollingFileAppender::new(rotation, dir, "events-bot.log");
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer);
+            (Some(layer), Some(guard))
+        }
+        Err(_) => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
+        .init();
+
+    guard
+}
+
 async fn database() -> sqlx::SqlitePool {
     let pool = sqlx::SqlitePool::connect(&env::var("DATABASE_URL").unwrap())
         .await